@@ -0,0 +1,105 @@
+//! Benchmarks do parse, dos motores de simulação e das conversões.
+//!
+//! As entradas ficam em `benches/fixtures/` e foram geradas com:
+//!
+//! ```bash
+//! afdn_animator --gerar=benches/fixtures/afd_2000.txt --estados=2000 --alfabeto=a,b --densidade=0.9 --finais=200 --semente=44
+//! afdn_animator --gerar=benches/fixtures/afn_lambda_200.txt --afn --estados=200 --alfabeto=a,b --densidade=0.3 --lambda=0.8 --finais=20 --semente=44
+//! afdn_animator --gerar=benches/fixtures/afn_30.txt --afn --estados=30 --alfabeto=a,b --densidade=0.4 --lambda=0.3 --finais=5 --semente=44
+//! ```
+//!
+//! e `palavra_100k.txt` possui 100000 símbolos sorteados de {a, b}.
+use afdn_animator::afdn::{afd, afn};
+use afdn_animator::compiled::{Dfa, Nfa, Symbols};
+use afdn_animator::convert::complete::{complete, TRAP};
+use afdn_animator::convert::{determinize::determinize, minimize::minimize};
+use afdn_animator::util::file::{self, ParsedFile};
+use afdn_animator::Automaton;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn fixture(name: &str) -> String {
+  std::fs::read_to_string(format!(
+    "{}/benches/fixtures/{}",
+    env!("CARGO_MANIFEST_DIR"),
+    name
+  ))
+  .expect("Fixture não encontrada")
+}
+
+fn tokens(word: &str, infos: &ParsedFile) -> Vec<String> {
+  file::tokenize(word, &infos.get_alphabet())
+}
+
+fn parsing(c: &mut Criterion) {
+  let content = fixture("afd_2000.txt");
+  c.bench_function("parse afd_2000", |b| {
+    b.iter(|| file::parse_str(black_box(&content)))
+  });
+  c.bench_function("compile afd_2000", |b| {
+    let infos = file::parse_str(&content);
+    b.iter(|| Dfa::new(&Automaton::from_parsed(black_box(&infos))))
+  });
+}
+
+fn dfa_simulation(c: &mut Criterion) {
+  // completo, para que a palavra seja sempre lida até o fim (sem travar)
+  let infos = complete(&file::parse_str(&fixture("afd_2000.txt")), TRAP, false);
+  let word = fixture("palavra_100k.txt");
+  let tokens = tokens(&word, &infos);
+  let automaton = Automaton::from_parsed(&infos);
+  let dfa = Dfa::new(&automaton).unwrap();
+
+  let mut group = c.benchmark_group("afd palavra_100k");
+  group.sample_size(20);
+  group.bench_function("motor", |b| {
+    b.iter(|| afd::accepts(&infos, black_box(&tokens)))
+  });
+  group.bench_function("compilado", |b| {
+    b.iter(|| dfa.accepts(Symbols::new(&automaton, black_box(&word))))
+  });
+  group.finish();
+}
+
+fn nfa_simulation(c: &mut Criterion) {
+  let infos = file::parse_str(&fixture("afn_lambda_200.txt"));
+  // a simulação por conjuntos (sem a tabela compilada) é bem mais lenta: usa somente o início da
+  // palavra
+  let word = &fixture("palavra_100k.txt")[..2000];
+  let tokens = tokens(word, &infos);
+  let automaton = Automaton::from_parsed(&infos);
+  let nfa = Nfa::new(&automaton);
+
+  let mut group = c.benchmark_group("afn lambda palavra_2k");
+  group.sample_size(20);
+  group.bench_function("motor", |b| {
+    b.iter(|| afn::accepts(&infos, black_box(&tokens)))
+  });
+  group.bench_function("compilado", |b| {
+    b.iter(|| nfa.accepts(Symbols::new(&automaton, black_box(word))))
+  });
+  group.finish();
+}
+
+fn conversions(c: &mut Criterion) {
+  let nfa = file::parse_str(&fixture("afn_30.txt"));
+  let afd = file::parse_str(&fixture("afd_2000.txt"));
+
+  let mut group = c.benchmark_group("conversões");
+  group.sample_size(10);
+  group.bench_function("determinize afn_30", |b| {
+    b.iter(|| determinize(black_box(&nfa)))
+  });
+  group.bench_function("minimize afd_2000", |b| {
+    b.iter(|| minimize(black_box(&afd)))
+  });
+  group.finish();
+}
+
+criterion_group!(
+  benches,
+  parsing,
+  dfa_simulation,
+  nfa_simulation,
+  conversions
+);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+//! Bytes arbitrários nunca devem causar pânico no parse: somente um `ParseError`.
+#![no_main]
+use afdn_animator::parsers::{try_parse_str, validate};
+use afdn_animator::util::file::{serialize, serialize_compact};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  let content = String::from_utf8_lossy(data);
+  if let Ok(infos) = try_parse_str(&content) {
+    let _ = validate(&infos);
+    let _ = infos.get_tokens();
+    let _ = infos.get_all_states();
+
+    // o autômato escrito de volta deve ser lido novamente sem erros
+    let serialized = serialize(&infos);
+    assert!(try_parse_str(&serialized).is_ok(), "{}", serialized);
+    let compact = serialize_compact(&infos);
+    assert!(try_parse_str(&compact).is_ok(), "{}", compact);
+  }
+});
@@ -0,0 +1,54 @@
+//! Parse seguido das simulações. O tamanho do autômato e da palavra é limitado para que o
+//! trabalho de cada entrada seja limitado (a determinização é exponencial no número de estados).
+//! Os motores devem concordar entre si.
+#![no_main]
+use afdn_animator::afdn::{afd, afn, tree};
+use afdn_animator::compiled::Compiled;
+use afdn_animator::convert::determinize::determinize;
+use afdn_animator::parsers::try_parse_str;
+use afdn_animator::{Automaton, Simulator};
+use libfuzzer_sys::fuzz_target;
+
+const MAX_STATES: usize = 64;
+const MAX_SYMBOLS: usize = 64;
+const MAX_DETERMINIZE: usize = 10;
+
+fuzz_target!(|data: &[u8]| {
+  let content = String::from_utf8_lossy(data);
+  let infos = match try_parse_str(&content) {
+    Ok(infos) => infos,
+    Err(_) => return,
+  };
+  let states = infos.get_all_states().len();
+  let word = infos.get_tokens();
+  if states > MAX_STATES || word.len() > MAX_SYMBOLS {
+    return;
+  }
+
+  let expected = afn::accepts(&infos, &word);
+  if infos.is_afd() {
+    assert_eq!(afd::accepts(&infos, &word), expected);
+  }
+
+  let automaton = Automaton::from_parsed(&infos);
+  let compiled = Compiled::new(&automaton);
+  assert_eq!(
+    compiled.accepts(word.iter().map(|e| automaton.symbol_id(e))),
+    expected
+  );
+  assert_eq!(Simulator::new(&automaton).run(&word), expected);
+
+  let steps = afn::simulate(&infos, &word);
+  assert!(steps.len() <= word.len() + 1);
+  let _ = tree::build(&infos, 1000);
+  let paths = afn::all_paths(&infos, 100, false);
+  assert_eq!(
+    paths.iter().any(|e| e.outcome == afn::Outcome::Accepted),
+    expected
+  );
+  assert!(afn::all_paths(&infos, 100, true).len() <= 100);
+
+  if states <= MAX_DETERMINIZE {
+    assert_eq!(afd::accepts(&determinize(&infos), &word), expected);
+  }
+});
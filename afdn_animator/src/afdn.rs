@@ -7,7 +7,10 @@ pub type NodeVec = Vec<Node>;
 
 pub mod afd {
 
-  use crate::util::{file::ParsedFile, menu};
+  use crate::automaton::Automaton;
+  use crate::dot;
+  use crate::util::file::{join_tokens, ParsedFile};
+  use crate::util::Animation;
 
   /// Exporta o hashmap para um arquivo dot equivalente.
   /// Todos os nós terão configuração padrão, com excessão do principal.
@@ -17,100 +20,128 @@ pub mod afd {
   /// * `hashmap` - Um hashmap contendo o grafo.
   /// * `node_to_color` - Key do nó que será colorido (atual).
   /// * `edge_to_color` - Aresta que será colorida (indica o nó futuro).
+  /// * `failed` - Se verdadeiro, o nó atual é colorido de vermelho (palavra rejeitada).
   ///
   /// ## Examples
   ///
-  /// ```rust
-  /// save_dot_file(&hashmap, &"s0", &"0");
+  /// ```rust,ignore
+  /// save_dot_file(&hashmap, &"s0", &"0", false);
   /// ```
   fn save_dot_file(
     infos: &ParsedFile,
     node_to_color: &String,
     edge_to_color: &String,
-    filename: &String,
+    failed: bool,
+    filename: &str,
   ) {
     debug!("#Graph Saving graph in file.");
 
-    let mut output = String::new();
-
-    // cria o cabeçalho
-    output.push_str("digraph G {\n");
-    output.push_str(
-      "\trankdir=LR;\n\toverlap=scale;\n\tsep=0.1;\n\tpad=1;\n\tnodesep=0.5;\n\tranksep=1;\n\n",
-    );
-    output.push_str("\tnode [style=\"rounded,filled\"]\n");
-    output.push_str("\tedge [color=\"gray\"]\n\n");
-    debug!("#Graph Header saved");
-
-    // configura os nós terminais
-    for node in infos.get_finish_state() {
-      let mut terminal: String = "\t".to_string() + node;
-      terminal.push_str(" [peripheries=2]\n");
-      output.push_str(&terminal);
+    // cabeçalho, nós terminais e nó inicial
+    let mut output = dot::header(infos);
+
+    // configura o nó atual (cor), caso não esteja oculto
+    if !infos.get_hidden_states().contains(node_to_color) {
+      let color = if failed { "#ad2a2a" } else { "#467050" };
+      output.push_str(&format!(
+        "\n\t{} [color=\"{}\", fontcolor=\"white\"]\n",
+        dot::dot_id(node_to_color),
+        color
+      ));
     }
-    debug!("#Graph Setup terminal nodes");
-
-    // configura a "seta" do nó inicial apontado para o primeiro nó
-    output.push_str("\n\tstart [label= \"\", shape=none,height=.0,width=.0]\n");
-    let initial: String = "\tstart -> ".to_string() + infos.get_initial_state() + "\n";
-    output.push_str(&initial);
-    debug!("#Graph Setup start node");
-
-    // configura o nó atual (cor)
-    output.push_str(&format!(
-      "\n\t{} [color=\"#467050\", fontcolor=\"white\"]\n",
-      node_to_color
-    ));
     debug!("#Graph Setup current_node: {}", &node_to_color);
 
     // insere as arestas (e nós)
-    debug!("#Graph - Walking in keys... {:#?}", infos.states.keys());
-    for origin in infos.states.keys() {
+    debug!(
+      "#Graph - Walking in keys... {:#?}",
+      infos.get_states().keys()
+    );
+    // em ordem, para que os arquivos gerados sejam sempre os mesmos
+    let mut keys: Vec<&String> = infos.get_states().keys().collect();
+    keys.sort();
+    for origin in keys {
       debug!("#Graph -\t CurrentNode: {}", origin);
 
-      let nodes = infos.states.get(origin).unwrap();
+      let nodes = infos.get_states().get(origin).unwrap();
       debug!("#Graph -\t ChildrenNodes: {:#?}", &nodes);
 
       for node in nodes {
+        // estados ocultos (e.g. armadilha) não são desenhados
+        let hidden = infos.get_hidden_states();
+        if hidden.contains(origin) || hidden.contains(&node.destination) {
+          continue;
+        }
+
         // configura a cor e label das arestas
-        let mut additional_configs: String = format!("[label=\"{}\"", &node.character);
+        let mut additional_configs: String =
+          format!("[{}", dot::edge_attributes(infos, origin, node));
 
-        if (origin == node_to_color) && (node.character == edge_to_color.to_string()) {
+        if (origin == node_to_color) && (node.character == *edge_to_color) {
           additional_configs = format!("{}, color=\"#ad2a2a\"", &additional_configs).to_string();
         }
         additional_configs = format!("{}]", &additional_configs);
 
         output.push_str(&format!(
           "\t{} -> {} {}\n",
-          &origin, &node.destination, &additional_configs
+          dot::dot_id(origin),
+          dot::dot_id(&node.destination),
+          &additional_configs
         ));
         debug!("#Graph -\t Settled")
       }
     }
 
-    output.push('}');
+    dot::save(output, filename);
+  }
 
-    // salva em arquivo
-    match std::fs::write(filename, output) {
-      Ok(_) => debug!("Saved file {}", filename),
-      Err(err) => panic!("{}", err),
+  /// Verifica se o AFD aceita a palavra, sem gerar nenhum quadro.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::afdn::afd::accepts;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+  /// let aceita: bool = accepts(&infos, &infos.get_tokens());
+  /// # assert!(aceita);
+  /// ```
+  pub fn accepts(infos: &ParsedFile, word: &[String]) -> bool {
+    let automaton = Automaton::from_parsed(infos);
+    let mut current_state = match automaton.initial_states().iter().next() {
+      Some(state) => *state,
+      None => return false,
+    };
+    for symbol in word {
+      let next = automaton
+        .symbol_id(symbol)
+        .and_then(|symbol| automaton.next(current_state, symbol));
+      match next {
+        Some(next) => current_state = next,
+        // transição ausente (ou símbolo fora do alfabeto): trava
+        None => return false,
+      }
     }
+    automaton.is_final(current_state)
   }
 
   /// Anda sobre o afd.
   /// Considerações:
-  /// - Assume-se que somente haverá **um único** estado inicial.
+  /// - Por ser um AFD, haverá **um único** estado inicial.
   ///
   /// ## Arguments
   ///
   /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `animation` - Onde salvar os quadros e se deve exibir o menu a cada passo.
   ///
   /// ## Examples
   ///
-  /// ```rust
-  /// let resultado:bool = run(&infos); // true or false
+  /// ```rust,no_run
+  /// # use afdn_animator::afdn::afd::run;
+  /// # use afdn_animator::util::Animation;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+  /// let resultado:bool = run(&infos, &Animation::default()); // true or false
   /// ```
-  pub fn run(infos: &ParsedFile) -> bool {
+  pub fn run(infos: &ParsedFile, animation: &Animation) -> bool {
     debug!("Running afd...");
     debug!("+ Creating the first dot file");
     self::save_dot_file(
@@ -118,27 +149,26 @@ pub mod afd {
       // os dois valores abaixo são para posições que nunca irão existir, dessa forma, nunca será colorido
       &"".to_string(),
       &"".to_string(),
-      &format!("./dot/dotfile_{}.dot", "00"),
+      false,
+      &animation.frame("00"),
     );
 
-    let word: Vec<String> = infos
-      .get_word()
-      .chars()
-      .collect::<Vec<char>>()
-      .iter()
-      .map(|e| e.to_string())
-      .collect();
-    debug!("Splitted the word into vec of chars");
+    let word: Vec<String> = infos.get_tokens();
+    debug!("Splitted the word into vec of symbols");
 
     let mut pos: usize = 0;
     let mut flag: bool;
-    let mut current_state: String = infos.get_initial_state().to_string();
+    // um AFD sempre possui um único estado inicial
+    let mut current_state: String = infos.get_initial_states()[0].to_string();
 
     debug!("Start walking");
     while pos < word.len() {
-      debug!("\n\t [Word {} is valid]", word[0..pos].join(""));
-      // obtêm todos os estados alcançáveis
-      let possible = infos.states.get(&current_state).unwrap();
+      debug!("\n\t [Word {} is valid]", join_tokens(&word[0..pos]));
+      // obtêm todos os estados alcançáveis (um estado sem transições de saída trava)
+      let possible = match infos.get_states().get(&current_state) {
+        Some(possible) => possible.as_slice(),
+        None => &[],
+      };
 
       let mut node_to_color = String::new();
       let mut edge_to_color = String::new();
@@ -173,54 +203,70 @@ pub mod afd {
       // ocorreu um erro durante o percurso do afd/afn
       if !flag {
         eprintln!(
-          "Palavra {} inválida. Válida até {}. Char de conflito {}",
+          "Palavra {} inválida. Válida até {}. Símbolo de conflito {}",
           infos.get_word(),
-          word[0..pos].join(""),
+          join_tokens(&word[0..pos]),
           word[pos]
         );
+        // último quadro: o estado em que travou, em vermelho
+        self::show_failure(infos, animation, &word[0..pos], &current_state, pos + 1);
         return false;
       }
 
       // exibe menu
-      loop {
-        debug!("\t - Showing menu");
-        let option: u8 = menu(word[0..pos].join(""));
-        if option == 1 {
-          self::save_dot_file(
-            infos,
-            &node_to_color,
-            &edge_to_color,
-            &format!("./dot/dotfile_{}.dot", pos),
-          );
-          // exit this loop
-          break;
-        } else if option == 0 {
-          break;
-        }
+      if animation.should_save(join_tokens(&word[0..pos])) {
+        self::save_dot_file(
+          infos,
+          &node_to_color,
+          &edge_to_color,
+          false,
+          &animation.frame(pos),
+        );
       }
     }
 
-    // se atingiu um estado final
+    // aceita somente se atingiu um estado final
     if infos.get_finish_state().contains(&current_state) {
       return true;
-    } else {
-      // caso contrário, não aceita a palavra
-      return false;
+    }
+
+    // caso contrário, o último quadro mostra o estado (não final) em vermelho
+    self::show_failure(infos, animation, &word, &current_state, pos + 1);
+    false
+  }
+
+  /// Exibe o menu uma última vez e, caso escolhido, salva o quadro da rejeição, i.e, o estado em
+  /// que a palavra travou (ou terminou sem ser final) em vermelho.
+  fn show_failure(
+    infos: &ParsedFile,
+    animation: &Animation,
+    consumed: &[String],
+    failed_state: &String,
+    frame: usize,
+  ) {
+    debug!("\t - Rejected");
+    if animation.should_save(join_tokens(consumed)) {
+      self::save_dot_file(
+        infos,
+        failed_state,
+        &"".to_string(),
+        true,
+        &animation.frame(frame),
+      );
     }
   }
 }
 
 pub mod afn {
-  use crate::file::ParsedFile;
-  use crate::util::menu;
+  use crate::automaton::{Automaton, Simulator};
+  use crate::convert::lambda::closure;
+  use crate::dot;
+  use crate::util::file::{join_tokens, ParsedFile};
+  use crate::util::Animation;
+  use std::collections::BTreeSet;
 
   pub const LAMBDA: &str = "/";
 
-  #[inline]
-  fn pad(n: usize) -> String {
-    "\t".repeat(n)
-  }
-
   /// Exporta o hashmap para um arquivo dot equivalente.
   /// Todos os nós terão configuração padrão, com excessão do principal.
   ///
@@ -233,60 +279,51 @@ pub mod afn {
   ///
   /// ## Examples
   ///
-  /// ```rust
+  /// ```rust,ignore
   /// save_dot_file(&hashmap, &"s0", &"0");
   /// ```
   fn save_dot_file(
     infos: &ParsedFile,
     node_to_color: &String,
     edge_to_color: usize,
-    filename: &String,
+    filename: &str,
   ) {
     debug!("#Graph Saving graph in file.");
 
-    let mut output = String::new();
-
-    // cria o cabeçalho
-    output.push_str("digraph G {\n");
-    output.push_str(
-      "\trankdir=LR;\n\toverlap=scale;\n\tsep=0.1;\n\tpad=1;\n\tnodesep=0.5;\n\tranksep=1;\n\n",
-    );
-    output.push_str("\tnode [style=\"rounded,filled\"]\n");
-    output.push_str("\tedge [color=\"gray\"]\n\n");
-    debug!("#Graph Header saved");
-
-    // configura os nós terminais
-    for node in infos.get_finish_state() {
-      let mut terminal: String = "\t".to_string() + node;
-      terminal.push_str(" [peripheries=2]\n");
-      output.push_str(&terminal);
-    }
-    debug!("#Graph Setup terminal nodes");
-
-    // configura a "seta" do nó inicial apontado para o primeiro nó
-    output.push_str("\n\tstart [label= \"\", shape=none,height=.0,width=.0]\n");
-    let initial: String = "\tstart -> ".to_string() + infos.get_initial_state() + "\n";
-    output.push_str(&initial);
-    debug!("#Graph Setup start node");
+    // cabeçalho, nós terminais e nó inicial
+    let mut output = dot::header(infos);
 
     // configura o nó atual (cor)
     output.push_str(&format!(
       "\n\t{} [color=\"#467050\", fontcolor=\"white\"]\n",
-      node_to_color
+      dot::dot_id(node_to_color)
     ));
     debug!("#Graph Setup current_node: {}", &node_to_color);
 
     // insere as arestas (e nós)
-    debug!("#Graph - Walking in keys... {:#?}", infos.states.keys());
-    for origin in infos.states.keys() {
+    debug!(
+      "#Graph - Walking in keys... {:#?}",
+      infos.get_states().keys()
+    );
+    // em ordem, para que os arquivos gerados sejam sempre os mesmos
+    let mut keys: Vec<&String> = infos.get_states().keys().collect();
+    keys.sort();
+    for origin in keys {
       debug!("#Graph -\t CurrentNode: {}", origin);
 
-      let nodes = infos.states.get(origin).unwrap();
+      let nodes = infos.get_states().get(origin).unwrap();
       debug!("#Graph -\t ChildrenNodes: {:#?}", &nodes);
 
       for (i, node) in nodes.iter().enumerate() {
+        // estados ocultos (e.g. armadilha) não são desenhados
+        let hidden = infos.get_hidden_states();
+        if hidden.contains(origin) || hidden.contains(&node.destination) {
+          continue;
+        }
+
         // configura a cor e label das arestas
-        let mut additional_configs: String = format!("[label=\"{}\"", &node.character);
+        let mut additional_configs: String =
+          format!("[{}", dot::edge_attributes(infos, origin, node));
 
         if (origin == node_to_color) && (i == edge_to_color) {
           additional_configs = format!("{}, color=\"#ad2a2a\"", &additional_configs).to_string();
@@ -295,141 +332,488 @@ pub mod afn {
 
         output.push_str(&format!(
           "\t{} -> {} {}\n",
-          &origin, &node.destination, &additional_configs
+          dot::dot_id(origin),
+          dot::dot_id(&node.destination),
+          &additional_configs
         ));
         debug!("#Graph -\t Settled")
       }
     }
 
-    output.push('}');
+    dot::save(output, filename);
+  }
+
+  /// Resultado de uma computação (ramo) do AFN.
+  #[derive(Debug, Clone, PartialEq)]
+  pub enum Outcome {
+    /// Consumiu toda a palavra e parou em um estado final.
+    Accepted,
+    /// Consumiu toda a palavra, mas não atingiu um estado final.
+    Rejected,
+    /// Não há transição para o próximo símbolo (travou antes do fim da palavra).
+    Stuck,
+  }
+
+  /// # Computation
+  /// Um caminho completo do AFN: estado de partida, transições realizadas e o resultado.
+  #[derive(Debug, Clone)]
+  pub struct Computation {
+    pub start: String,
+    /// Cada nó indica o símbolo consumido (ou `/`) e o estado de destino.
+    pub steps: super::NodeVec,
+    pub outcome: Outcome,
+  }
+  impl Computation {
+    /// Formata o caminho como `q0 --a--> q1 --/--> q2`.
+    pub fn describe(&self) -> String {
+      let mut output = self.start.to_string();
+      for step in &self.steps {
+        output.push_str(&format!(" --{}--> {}", step.character, step.destination));
+      }
+      output
+    }
+  }
+
+  /// Estados, para cada posição da palavra, a partir dos quais ainda é possível aceitar o restante
+  /// dela: `alive[i]` contém `q` se `(q, i)` alcança `(f, n)`, com `f` final. Calculado de trás
+  /// para frente, em tempo linear no tamanho da palavra.
+  fn alive(infos: &ParsedFile, word: &[String]) -> Vec<BTreeSet<String>> {
+    let states = infos.get_all_states();
+    let closures: Vec<(&String, BTreeSet<String>)> = states
+      .iter()
+      .map(|e| (e, closure(infos.get_states(), vec![e.to_string()])))
+      .collect();
+    let finals = infos.get_finish_state();
 
-    // salva em arquivo
-    match std::fs::write(filename, output) {
-      Ok(_) => debug!("Saved file {}", filename),
-      Err(err) => panic!("{}", err),
+    let mut alive: Vec<BTreeSet<String>> = vec![BTreeSet::new(); word.len() + 1];
+    alive[word.len()] = closures
+      .iter()
+      .filter(|(_, reached)| reached.iter().any(|e| finals.contains(e)))
+      .map(|(state, _)| state.to_string())
+      .collect();
+    for pos in (0..word.len()).rev() {
+      let next = &alive[pos + 1];
+      let current: BTreeSet<String> = closures
+        .iter()
+        .filter(|(_, reached)| {
+          reached.iter().any(|p| {
+            infos.get_states().get(p).is_some_and(|nodes| {
+              nodes
+                .iter()
+                .any(|e| e.character == word[pos] && next.contains(&e.destination))
+            })
+          })
+        })
+        .map(|(state, _)| state.to_string())
+        .collect();
+      alive[pos] = current;
     }
+    alive
   }
 
-  fn afn_walking(
-    word: &Vec<String>,
-    curr_pos: usize,
-    current_state: &String,
+  /// Busca em profundidade de todas as computações, partindo de `state` na posição `pos`.
+  /// Para evitar laços infinitos, um ramo não revisita (via lambda) um estado já visitado
+  /// na mesma posição da palavra. Caso `alive` seja informado (somente caminhos de aceitação),
+  /// os ramos que não podem mais aceitar são podados, evitando a busca exponencial.
+  #[allow(clippy::too_many_arguments)]
+  fn walk_all(
     infos: &ParsedFile,
-    p: Option<super::NodeVec>,
-  ) -> (bool, Option<super::NodeVec>) {
-    debug!("{}  Word index: {}", self::pad(curr_pos), curr_pos);
-    debug!(
-      "{}> CURRENT STATE: #{}",
-      self::pad(curr_pos),
-      &current_state
-    );
-    let mut path = p.clone();
+    word: &[String],
+    pos: usize,
+    state: &str,
+    computation: &mut Computation,
+    lambda_visited: &[String],
+    limit: usize,
+    alive: Option<&[BTreeSet<String>]>,
+    found: &mut Vec<Computation>,
+  ) {
+    if found.len() >= limit {
+      return;
+    }
 
-    // verifica se é uma folha (se estourou o máximo de char perdido pela palavra)
-    if curr_pos == word.len() {
-      // então verifica se atingiu um estado final
-      if infos.get_finish_state().contains(&current_state) {
-        return (true, Some(path.unwrap()));
-      } else {
-        // caso contrário, não aceita a palavra
-        debug!(
-          "{}~~~~Overflow, back one leaf.`t'~~~~\n",
-          self::pad(curr_pos)
+    // consumiu toda a palavra em um estado final: caminho aceito, mas as transições lambda
+    // restantes ainda podem levar a outros caminhos de aceitação
+    let accepted = pos == word.len() && infos.get_finish_state().iter().any(|e| e == state);
+    if accepted {
+      let mut accepted = computation.clone();
+      accepted.outcome = Outcome::Accepted;
+      found.push(accepted);
+    }
+
+    let mut moved = false;
+    if let Some(possible) = infos.get_states().get(state) {
+      for p in possible {
+        let next_pos = if p.character == LAMBDA {
+          if lambda_visited.contains(&p.destination) {
+            continue;
+          }
+          pos
+        } else if pos < word.len() && word[pos] == p.character {
+          pos + 1
+        } else {
+          continue;
+        };
+        if let Some(alive) = alive {
+          if !alive[next_pos].contains(&p.destination) {
+            continue;
+          }
+        }
+        moved = true;
+
+        // ao consumir um símbolo, reinicia os estados visitados via lambda
+        let visited = if next_pos == pos {
+          let mut visited = lambda_visited.to_vec();
+          visited.push(p.destination.to_string());
+          visited
+        } else {
+          vec![p.destination.to_string()]
+        };
+
+        computation.steps.push(p.clone());
+        self::walk_all(
+          infos,
+          word,
+          next_pos,
+          &p.destination,
+          computation,
+          &visited,
+          limit,
+          alive,
+          found,
         );
-        return (false, None);
+        computation.steps.pop();
+
+        if found.len() >= limit {
+          return;
+        }
+      }
+    }
+
+    // folha que não aceita a palavra
+    if !moved && !accepted && alive.is_none() {
+      let mut rejected = computation.clone();
+      rejected.outcome = if pos == word.len() {
+        Outcome::Rejected
+      } else {
+        Outcome::Stuck
+      };
+      found.push(rejected);
+    }
+  }
+
+  /// Enumera todos os caminhos de aceitação (e, opcionalmente, os de rejeição) do AFN para a
+  /// palavra do arquivo, partindo de cada um dos estados iniciais.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `limit` - Número máximo de caminhos retornados. Como os ramos que não podem mais aceitar
+  ///   são podados (e, com `include_rejecting`, toda folha visitada é retornada), o trabalho também
+  ///   é limitado por ele, e não somente o resultado.
+  /// * `include_rejecting` - Se verdadeiro, também retorna os caminhos que rejeitam/travam.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::afdn::afn::all_paths;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// for c in all_paths(&infos, 10, false) { println!("{}", c.describe()); }
+  /// ```
+  pub fn all_paths(infos: &ParsedFile, limit: usize, include_rejecting: bool) -> Vec<Computation> {
+    let word: Vec<String> = infos.get_tokens();
+    let mut found: Vec<Computation> = Vec::new();
+    // sem os caminhos de rejeição, somente os ramos que ainda podem aceitar são explorados
+    let alive = if include_rejecting {
+      None
+    } else {
+      Some(self::alive(infos, &word))
+    };
+
+    for initial in infos.get_initial_states() {
+      if let Some(alive) = &alive {
+        if !alive[0].contains(initial) {
+          continue;
+        }
       }
+      let mut computation = Computation {
+        start: initial.to_string(),
+        steps: Vec::new(),
+        outcome: Outcome::Stuck,
+      };
+      self::walk_all(
+        infos,
+        &word,
+        0,
+        initial,
+        &mut computation,
+        &[initial.to_string()],
+        limit,
+        alive.as_deref(),
+        &mut found,
+      );
     }
 
-    // obtêm todos os estados alcançáveis a partir do estado atual
-    let possible = infos.states.get(current_state).unwrap();
+    debug!("All paths: {:#?}", &found);
+    found
+  }
 
-    debug!("{}++ Walking over node possibilities", self::pad(curr_pos),);
-    for p in possible {
-      if p.character == LAMBDA {
-        debug!(
-          "\t{}-- State: {} => Matched {}",
-          self::pad(curr_pos),
+  /// Gera uma série de arquivos dot para cada caminho (`caminho_<k>_<passo>.dot`), colorindo o
+  /// estado atual e a aresta usada em cada passo.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `computations` - Caminhos retornados pelo [`all_paths`].
+  /// * `dir` - Diretório onde salvar os arquivos.
+  pub fn save_paths(infos: &ParsedFile, computations: &[Computation], dir: &str) {
+    for (k, computation) in computations.iter().enumerate() {
+      let mut current_state = computation.start.to_string();
+      for (step, node) in computation.steps.iter().enumerate() {
+        // posição da aresta usada no vetor de transições do estado atual
+        let edge_to_color = infos
+          .get_states()
+          .get(&current_state)
+          .and_then(|possible| possible.iter().position(|e| e == node))
+          .unwrap_or(usize::MAX);
+
+        self::save_dot_file(
+          infos,
           &current_state,
-          &p.character
+          edge_to_color,
+          &format!("{}/caminho_{}_{:02}.dot", dir, k, step),
         );
-        let next_state = p.destination.to_string();
-        debug!("\t{}-- Next state: {}", self::pad(curr_pos), &next_state);
-
-        // adiciona o próximo estado no path
-        let next = super::Node {
-          character: p.character.to_string(),
-          // aqui, destination na verdade trata-se do nó atual
-          destination: next_state.to_string(),
-        };
-        let mut pt: super::NodeVec = path.unwrap();
-        pt.push(next);
+        current_state = node.destination.to_string();
+      }
 
-        path = Some(pt);
+      // último quadro: somente o estado em que a computação parou
+      self::save_dot_file(
+        infos,
+        &current_state,
+        usize::MAX,
+        &format!("{}/caminho_{}_{:02}.dot", dir, k, computation.steps.len()),
+      );
+    }
+  }
 
-        // muda para o próximo estado
-        let (found, all_path) = self::afn_walking(word, curr_pos, &next_state, infos, path.clone());
-        debug!("{}Backed: Curr_pos {:#?}", self::pad(curr_pos), curr_pos);
-        if found {
-          debug!("\t{}-- *FOUND* the word. It matches", self::pad(curr_pos),);
-          return (true, all_path);
-        } else {
-          // como adicionou antes de decer, é necessário remover antes de continuar
-          let mut pt = path.unwrap();
-          pt.pop();
-          path = Some(pt);
+  /// # Step
+  /// Um passo da simulação do AFN por conjuntos de estados.
+  #[derive(Debug, Clone)]
+  pub struct Step {
+    /// Estados ativos (já com o fecho lambda) após consumir os `pos` primeiros símbolos.
+    pub active: BTreeSet<String>,
+    /// Arestas disparadas neste passo (as que consumiram o símbolo e as lambda do fecho).
+    pub fired: Vec<(String, super::Node)>,
+    /// Estados ativos no passo anterior que não possuíam transição para o símbolo (morreram).
+    pub died: BTreeSet<String>,
+  }
+
+  /// Arestas lambda usadas dentro de um fecho (origem e destino pertencem ao conjunto).
+  fn lambda_edges(infos: &ParsedFile, active: &BTreeSet<String>) -> Vec<(String, super::Node)> {
+    let mut fired = Vec::new();
+    for state in active {
+      if let Some(possible) = infos.get_states().get(state) {
+        for p in possible {
+          if p.character == LAMBDA && active.contains(&p.destination) {
+            fired.push((state.to_string(), p.clone()));
+          }
         }
-      } else if word[curr_pos] == p.character {
-        // consome a letra
-        debug!(
-          "\t{}-- State: {} => Matched {}",
-          self::pad(curr_pos),
-          &current_state,
-          &p.character
-        );
-        let next_state = p.destination.to_string();
-        debug!("\t{}-- Next state: {}", self::pad(curr_pos), &next_state);
-
-        // adiciona o próximo estado no path
-        let next = super::Node {
-          character: p.character.to_string(),
-          // aqui, destination na verdade trata-se do nó atual
-          destination: next_state.to_string(),
-        };
-        let mut pt: super::NodeVec = path.unwrap();
-        pt.push(next);
-
-        path = Some(pt);
-
-        // muda para o próximo estado
-        let (found, all_path) =
-          self::afn_walking(word, curr_pos + 1, &next_state, infos, path.clone());
-        debug!("{}Backed: Curr_pos {:#?}", self::pad(curr_pos), curr_pos);
-        if found {
-          debug!("\t{}-- *FOUND* the word. It matches", self::pad(curr_pos),);
-          return (true, all_path);
-        } else {
-          // como adicionou antes de decer, é necessário remover antes de continuar
-          let mut pt = path.unwrap();
-          pt.pop();
-          path = Some(pt);
+      }
+    }
+    fired
+  }
+
+  /// Conjunto de estados alcançados a partir de `active` ao consumir `symbol` (já com o fecho
+  /// lambda). É o passo básico da simulação por conjuntos.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::afdn::afn::advance;
+  /// # use std::collections::BTreeSet;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// # let active: BTreeSet<String> = vec!["q0".to_string()].into_iter().collect();
+  /// let next = advance(&infos, &active, "a");
+  /// # assert_eq!(next.len(), 2);
+  /// ```
+  pub fn advance(infos: &ParsedFile, active: &BTreeSet<String>, symbol: &str) -> BTreeSet<String> {
+    let mut reached: Vec<String> = Vec::new();
+    // lambda não é um símbolo que possa ser lido da palavra
+    if symbol == LAMBDA {
+      return BTreeSet::new();
+    }
+    for state in active {
+      if let Some(possible) = infos.get_states().get(state) {
+        for p in possible.iter().filter(|e| e.character == symbol) {
+          reached.push(p.destination.to_string());
         }
       }
     }
-    return (false, None);
+    closure(infos.get_states(), reached)
   }
 
-  /// Retorna um possível caminho que leva à resposta
+  /// Simula o AFN mantendo o conjunto de estados ativos, i.e, todos os ramos de uma só vez.
+  /// Retorna um passo para a posição inicial e um para cada símbolo consumido, parando antes caso
+  /// o conjunto fique vazio (este último passo, vazio, também é retornado).
   ///
   /// ## Arguments
   ///
-  /// * `infos` - As informações com base no arquivo de entrada
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `word` - Palavra já dividida em símbolos.
   ///
   /// ## Examples
   ///
   /// ```rust
-  /// let o = run_afn(&infos);
+  /// # use afdn_animator::afdn::afn::simulate;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let steps = simulate(&infos, &infos.get_tokens());
+  /// let last = &steps.last().unwrap().active; // estados ativos ao fim da simulação
   /// ```
-  pub fn run(infos: &ParsedFile) -> bool {
+  pub fn simulate(infos: &ParsedFile, word: &[String]) -> Vec<Step> {
+    let mut active = closure(infos.get_states(), infos.get_initial_states().to_vec());
+    let mut steps = vec![Step {
+      fired: self::lambda_edges(infos, &active),
+      active: active.clone(),
+      died: BTreeSet::new(),
+    }];
+
+    for symbol in word {
+      if active.is_empty() {
+        break;
+      }
+
+      // estados alcançados ao consumir o símbolo, seguidos do fecho lambda
+      let mut reached: Vec<String> = Vec::new();
+      let mut fired: Vec<(String, super::Node)> = Vec::new();
+      let mut alive: BTreeSet<String> = BTreeSet::new();
+      for state in &active {
+        if let Some(possible) = infos.get_states().get(state) {
+          for p in possible.iter().filter(|e| &e.character == symbol) {
+            reached.push(p.destination.to_string());
+            fired.push((state.to_string(), p.clone()));
+            alive.insert(state.to_string());
+          }
+        }
+      }
+      let next = closure(infos.get_states(), reached);
+      fired.extend(self::lambda_edges(infos, &next));
+
+      let died: BTreeSet<String> = active
+        .iter()
+        .filter(|e| !alive.contains(*e) && !next.contains(*e))
+        .cloned()
+        .collect();
+      debug!("Symbol {} -> active {:?}, died {:?}", symbol, &next, &died);
+
+      active = next;
+      steps.push(Step {
+        active: active.clone(),
+        fired,
+        died,
+      });
+    }
+
+    steps
+  }
+
+  /// Verifica se o AFN aceita a palavra (simulação por conjuntos), sem gerar nenhum quadro.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::afdn::afn::accepts;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let aceita: bool = accepts(&infos, &infos.get_tokens());
+  /// # assert!(aceita);
+  /// ```
+  pub fn accepts(infos: &ParsedFile, word: &[String]) -> bool {
+    Simulator::new(&Automaton::from_parsed(infos)).run(word)
+  }
+
+  /// Exporta um quadro da simulação por conjuntos: todos os estados ativos preenchidos, as arestas
+  /// disparadas no passo coloridas e os estados que acabaram de morrer em cinza.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - Arquivo parsed, contendo as informações
+  /// * `step` - Passo retornado pelo [`simulate`].
+  /// * `failed` - Estados que falharam (rejeição), coloridos de vermelho.
+  /// * `filename` - Nome e local de onde salvar o arquivo dot.
+  fn save_set_dot_file(infos: &ParsedFile, step: &Step, failed: &BTreeSet<String>, filename: &str) {
+    debug!("#Graph Saving set graph in file.");
+    let mut output = dot::header(infos);
+
+    // estados ocultos (e.g. armadilha) não são desenhados
+    let hidden = infos.get_hidden_states();
+    let visible = |state: &&String| !hidden.contains(state);
+    for state in step.died.difference(failed).filter(visible) {
+      output.push_str(&format!(
+        "\n\t{} [color=\"#b0b0b0\", fontcolor=\"#707070\"]",
+        dot::dot_id(state)
+      ));
+    }
+    for state in step.active.iter().filter(visible) {
+      output.push_str(&format!(
+        "\n\t{} [color=\"#467050\", fontcolor=\"white\"]",
+        dot::dot_id(state)
+      ));
+    }
+    for state in failed.iter().filter(visible) {
+      output.push_str(&format!(
+        "\n\t{} [color=\"#ad2a2a\", fontcolor=\"white\"]",
+        dot::dot_id(state)
+      ));
+    }
+    output.push('\n');
+
+    let mut keys: Vec<&String> = infos.get_states().keys().collect();
+    keys.sort();
+    for origin in keys {
+      for node in infos.get_states().get(origin).unwrap() {
+        if hidden.contains(origin) || hidden.contains(&node.destination) {
+          continue;
+        }
+
+        let mut additional_configs: String =
+          format!("[{}", dot::edge_attributes(infos, origin, node));
+        if step.fired.contains(&(origin.to_string(), node.clone())) {
+          additional_configs = format!("{}, color=\"#ad2a2a\"", &additional_configs);
+        }
+        additional_configs = format!("{}]", &additional_configs);
+
+        output.push_str(&format!(
+          "\t{} -> {} {}\n",
+          dot::dot_id(origin),
+          dot::dot_id(&node.destination),
+          additional_configs
+        ));
+      }
+    }
+
+    dot::save(output, filename);
+  }
+
+  /// Simula o AFN por conjuntos de estados, exibindo o menu a cada passo (para salvar o quadro).
+  /// Em caso de rejeição, no último quadro os estados que falharam ficam em vermelho.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - As informações com base no arquivo de entrada
+  /// * `animation` - Onde salvar os quadros e se deve exibir o menu a cada passo.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust,no_run
+  /// # use afdn_animator::afdn::afn::run;
+  /// # use afdn_animator::util::Animation;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let resultado: bool = run(&infos, &Animation::default()); // true or false
+  /// ```
+  pub fn run(infos: &ParsedFile, animation: &Animation) -> bool {
     debug!("Running AFN...");
 
     debug!("+ Creating the first dot file");
@@ -437,79 +821,241 @@ pub mod afn {
       infos,
       // os dois valores abaixo são para posições que nunca irão existir, dessa forma, nunca será colorido
       &"".to_string(),
-      10000,
-      &format!("./dot/dotfile_{}.dot", "00"),
+      usize::MAX,
+      &animation.frame("00"),
     );
 
-    let word: Vec<String> = infos
-      .get_word()
-      .chars()
-      .collect::<Vec<char>>()
-      .iter()
-      .map(|e| e.to_string())
-      .collect();
-    debug!("Splitted the word into vec of chars");
+    let word: Vec<String> = infos.get_tokens();
+    debug!("Splitted the word into vec of symbols");
+
+    let steps = self::simulate(infos, &word);
+    let last = steps.last().unwrap();
+    let worked = steps.len() == word.len() + 1
+      && last
+        .active
+        .iter()
+        .any(|e| infos.get_finish_state().contains(e));
+    debug!(" @ AFN -- Worked?: {}\t STEPS: {:#?}", &worked, &steps);
+
+    for (pos, step) in steps.iter().enumerate() {
+      // na rejeição, o último quadro mostra em vermelho os estados que falharam
+      // (se esvaziou, os que morreram neste passo)
+      let mut failed: BTreeSet<String> = BTreeSet::new();
+      if !worked && pos == steps.len() - 1 {
+        failed = if step.active.is_empty() {
+          step.died.clone()
+        } else {
+          step.active.clone()
+        };
+      }
 
-    let mut pos: usize = 0;
-    let mut current_state: String = infos.get_initial_state().to_string();
+      // exibe menu
+      if animation.should_save(join_tokens(&word[0..pos])) {
+        debug!("+ Creating dot file");
+        self::save_set_dot_file(infos, step, &failed, &animation.frame(pos));
+      }
+    }
 
-    debug!("Start walking");
-    let (worked, walked_path) =
-      self::afn_walking(&word, pos, &current_state, infos, Some(Vec::new()));
+    worked
+  }
+}
+
+pub mod tree {
+  use super::afn::{Outcome, LAMBDA};
+  use crate::util::file::{join_tokens, ParsedFile};
+
+  /// # TreeNode
+  /// Uma configuração `(estado, posição)` da árvore de computação de um AFN.
+  #[derive(Debug, Clone)]
+  pub struct TreeNode {
+    pub state: String,
+    /// Quantos símbolos da palavra já foram consumidos.
+    pub pos: usize,
+    /// Ramos: símbolo consumido (ou `/`) e a configuração alcançada.
+    pub children: Vec<(String, TreeNode)>,
+    /// Resultado, caso seja uma folha ou uma configuração de aceitação (que ainda pode seguir por
+    /// `/`). `None` indica que não foi expandida (limite atingido).
+    pub outcome: Option<Outcome>,
+    /// Se existe alguma folha de aceitação nesta subárvore.
+    pub accepting: bool,
+  }
 
-    debug!(" @ AFN -- Worked?: {}\t PATH: {:?}", &worked, &walked_path);
-    if !worked {
-      // force exit
-      return false;
+  /// Expande a configuração `(state, pos)`, descontando de `budget` cada nó criado.
+  fn expand(
+    infos: &ParsedFile,
+    word: &[String],
+    state: &str,
+    pos: usize,
+    lambda_visited: &[String],
+    budget: &mut usize,
+  ) -> TreeNode {
+    let mut node = TreeNode {
+      state: state.to_string(),
+      pos,
+      children: Vec::new(),
+      outcome: None,
+      accepting: false,
+    };
+
+    // consumiu toda a palavra em um estado final, mas ainda segue as lambdas (fecho)
+    if pos == word.len() && infos.get_finish_state().iter().any(|e| e == state) {
+      node.outcome = Some(Outcome::Accepted);
+      node.accepting = true;
     }
 
-    // anda sobre o vetor de saída (caminho)
-    for node in walked_path.unwrap() {
-      // exibe menu
-      loop {
-        debug!("\t - Showing menu");
-        let option: u8 = menu(word[0..pos].join(""));
-        if option == 1 {
-          debug!("Menu called");
-          // obtém os estados alcançáveis
-          let possible_states = infos.states.get(&current_state).unwrap();
-          debug!("Possible states {:#?}", &possible_states);
-
-          // obtém a posição da aresta que bate com o node
-          debug!("Looping");
-          let mut edge_to_color = 0;
-          loop {
-            debug!(
-              "Comparing if {:?} is equal to {:?}",
-              &node, possible_states[edge_to_color]
-            );
-            if node == possible_states[edge_to_color] {
-              break;
-            }
-            edge_to_color += 1;
+    let mut moved = false;
+    if let Some(possible) = infos.get_states().get(state) {
+      for p in possible {
+        let next_pos = if p.character == LAMBDA {
+          // evita laços lambda infinitos na mesma posição
+          if lambda_visited.contains(&p.destination) {
+            continue;
           }
+          pos
+        } else if pos < word.len() && word[pos] == p.character {
+          pos + 1
+        } else {
+          continue;
+        };
+        moved = true;
 
-          debug!("+ Creating dot file");
-          self::save_dot_file(
-            infos,
-            &current_state,
-            edge_to_color,
-            &format!("./dot/dotfile_{}.dot", pos),
-          );
-
-          current_state = node.destination.clone();
-          // exit this loop
-          pos += 1;
-          break;
-        } else if option == 0 {
-          debug!("Menu called");
-          pos += 1;
-          break;
+        if *budget == 0 {
+          // limite atingido, o nó fica sem ser expandido
+          return node;
         }
+        *budget -= 1;
+
+        let visited = if next_pos == pos {
+          let mut visited = lambda_visited.to_vec();
+          visited.push(p.destination.to_string());
+          visited
+        } else {
+          vec![p.destination.to_string()]
+        };
+        let child = self::expand(infos, word, &p.destination, next_pos, &visited, budget);
+        node.accepting |= child.accepting;
+        node.children.push((p.character.to_string(), child));
       }
     }
 
-    return worked;
+    if !moved && !node.accepting {
+      node.outcome = Some(if pos == word.len() {
+        Outcome::Rejected
+      } else {
+        Outcome::Stuck
+      });
+    }
+    node
+  }
+
+  /// Monta a árvore de computação da palavra do arquivo, com uma raiz para cada estado inicial.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `max_nodes` - Número máximo de nós da árvore (evita explosão em autômatos grandes).
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::afdn::tree::build;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let roots = build(&infos, 500);
+  /// ```
+  pub fn build(infos: &ParsedFile, max_nodes: usize) -> Vec<TreeNode> {
+    let word: Vec<String> = infos.get_tokens();
+    let mut budget = max_nodes;
+
+    infos
+      .get_initial_states()
+      .iter()
+      .map(|initial| {
+        self::expand(
+          infos,
+          &word,
+          initial,
+          0,
+          &[initial.to_string()],
+          &mut budget,
+        )
+      })
+      .collect()
+  }
+
+  /// Escreve recursivamente os nós e arestas da árvore. Retorna o id do nó escrito.
+  fn write_node(
+    node: &TreeNode,
+    word: &[String],
+    next_id: &mut usize,
+    output: &mut String,
+  ) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+
+    // configuração no formato (estado, palavra restante)
+    let rest = match join_tokens(&word[node.pos..]) {
+      rest if rest.is_empty() => "ε".to_string(),
+      rest => rest,
+    };
+    let style = match node.outcome {
+      Some(Outcome::Accepted) => ", peripheries=2, color=\"#467050\", fontcolor=\"white\"",
+      Some(Outcome::Rejected) => ", color=\"#ad2a2a\", fontcolor=\"white\"",
+      Some(Outcome::Stuck) => ", color=\"#d9a441\"",
+      None if node.children.is_empty() => ", style=\"rounded,dashed\"",
+      None => "",
+    };
+    let label = format!("({}, {})", node.state, rest);
+    output.push_str(&format!(
+      "\tn{} [label={}{}]\n",
+      id,
+      crate::dot::dot_id(&label),
+      style
+    ));
+
+    for (symbol, child) in &node.children {
+      let child_id = self::write_node(child, word, next_id, output);
+      let highlight = if child.accepting {
+        ", color=\"#467050\", penwidth=2"
+      } else {
+        ""
+      };
+      output.push_str(&format!(
+        "\tn{} -> n{} [label={}{}]\n",
+        id,
+        child_id,
+        crate::dot::dot_id(symbol),
+        highlight
+      ));
+    }
+    id
+  }
+
+  /// Exporta a árvore de computação para um arquivo dot. As folhas de aceitação ficam em verde,
+  /// as de rejeição em vermelho e as que travaram em amarelo. Os ramos que levam à aceitação são
+  /// destacados.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `roots` - Árvore retornada pelo [`build`].
+  /// * `filename` - Nome e local de onde salvar o arquivo dot.
+  pub fn save_dot_file(infos: &ParsedFile, roots: &[TreeNode], filename: &str) {
+    debug!("#Tree Saving computation tree in file.");
+    let word: Vec<String> = infos.get_tokens();
+
+    let mut output = String::new();
+    output.push_str("digraph T {\n");
+    output.push_str("\trankdir=TB;\n\tnodesep=0.3;\n\tranksep=0.5;\n\n");
+    output.push_str("\tnode [shape=box, style=\"rounded,filled\"]\n");
+    output.push_str("\tedge [color=\"gray\"]\n\n");
+
+    let mut next_id = 0;
+    for root in roots {
+      self::write_node(root, &word, &mut next_id, &mut output);
+    }
+
+    crate::dot::save(output, filename);
   }
 }
 
@@ -528,6 +1074,213 @@ mod tests {
     assert_eq!(a, b, "Nodes aren't equals");
   }
 
+  #[test]
+  fn enumerates_all_accepting_paths() {
+    use super::afn::{all_paths, Outcome};
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    // q0 -a-> q1, q0 -a-> q2, q1 -/-> q2, q2 -/-> q1 (laço lambda), q2 final
+    let automaton = |word: &str| {
+      let node = |character: &str, destination: &str| super::Node {
+        character: character.to_string(),
+        destination: destination.to_string(),
+      };
+      let mut states = HashMap::new();
+      states.insert(
+        "q0".to_string(),
+        vec![node("a", "q1"), node("a", "q2"), node("b", "q0")],
+      );
+      states.insert("q1".to_string(), vec![node("/", "q2")]);
+      states.insert("q2".to_string(), vec![node("/", "q1")]);
+      ParsedFile::new(
+        vec!["q0".to_string()],
+        vec!["q2".to_string()],
+        states,
+        word.to_string(),
+      )
+    };
+
+    let accepted = all_paths(&automaton("a"), 10, false);
+    let described: Vec<String> = accepted.iter().map(|e| e.describe()).collect();
+    assert_eq!(described, vec!["q0 --a--> q1 --/--> q2", "q0 --a--> q2"]);
+    assert!(accepted.iter().all(|e| e.outcome == Outcome::Accepted));
+    assert_eq!(all_paths(&automaton("a"), 1, false).len(), 1);
+
+    // "b" termina em q0 (não final) e "ab" trava após o laço lambda
+    let rejected = all_paths(&automaton("b"), 10, true);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].outcome, Outcome::Rejected);
+    let stuck = all_paths(&automaton("ab"), 10, true);
+    assert!(!stuck.is_empty());
+    assert!(stuck.iter().all(|e| e.outcome == Outcome::Stuck));
+  }
+
+  #[test]
+  fn prunes_paths_that_cannot_accept() {
+    use super::afn::all_paths;
+    use crate::util::file::parse_str;
+
+    // 2^40 caminhos, nenhum deles alcança o estado final
+    let word = vec!["a"; 40].join(" ");
+    let infos = parse_str(&format!(
+      "q0 ; q2\nq0 a > q0\nq0 a > q1\nq1 a > q0\nq1 a > q1\nwrd : {}",
+      word
+    ));
+    assert!(all_paths(&infos, 10, false).is_empty());
+    assert_eq!(all_paths(&infos, 10, true).len(), 10);
+  }
+
+  #[test]
+  fn follows_lambda_edges_after_the_word() {
+    use super::afn::all_paths;
+    use crate::util::file::parse_str;
+
+    let infos = parse_str("q0 ; q1 q2\nq0 a > q1\nq1 / > q2\nwrd : a");
+    let described: Vec<String> = all_paths(&infos, 10, false)
+      .iter()
+      .map(|e| e.describe())
+      .collect();
+    assert_eq!(described, vec!["q0 --a--> q1", "q0 --a--> q1 --/--> q2"]);
+  }
+
+  #[test]
+  fn builds_computation_tree() {
+    use super::afn::Outcome;
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    // q0 -a-> q0, q0 -a-> q1 (final)
+    let node = |character: &str, destination: &str| super::Node {
+      character: character.to_string(),
+      destination: destination.to_string(),
+    };
+    let mut states = HashMap::new();
+    states.insert("q0".to_string(), vec![node("a", "q0"), node("a", "q1")]);
+    let infos = ParsedFile::new(
+      vec!["q0".to_string()],
+      vec!["q1".to_string()],
+      states,
+      "aa".to_string(),
+    );
+
+    let roots = super::tree::build(&infos, 100);
+    assert_eq!(roots.len(), 1);
+    let root = &roots[0];
+    assert!(root.accepting);
+    assert_eq!(root.children.len(), 2);
+
+    // (q0, a) -a-> (q0, ε) rejeita e (q0, a) -a-> (q1, ε) aceita
+    let (_, first) = &root.children[0];
+    assert_eq!(first.children[0].1.outcome, Some(Outcome::Rejected));
+    assert_eq!(first.children[1].1.outcome, Some(Outcome::Accepted));
+    // (q1, a) trava
+    let (_, second) = &root.children[1];
+    assert_eq!(second.outcome, Some(Outcome::Stuck));
+    assert!(!second.accepting);
+
+    // com o limite, a raiz não é totalmente expandida
+    let truncated = super::tree::build(&infos, 1);
+    assert_eq!(truncated[0].children.len(), 1);
+  }
+
+  #[test]
+  fn starts_from_every_initial_state() {
+    // p0 -/-> p1 e q0 -a-> q1: o conjunto inicial é o fecho de todos os iniciais
+    let infos = crate::util::file::parse_str("p0 q0 ; p1 q1\np0 / > p1\nq0 a > q1\nwrd : a");
+    let steps = super::afn::simulate(&infos, &infos.get_tokens());
+    let start: Vec<&str> = steps[0].active.iter().map(|e| e.as_str()).collect();
+    assert_eq!(start, vec!["p0", "p1", "q0"]);
+    let last: Vec<&str> = steps[1].active.iter().map(|e| e.as_str()).collect();
+    assert_eq!(last, vec!["q1"]);
+    assert!(super::afn::accepts(&infos, &infos.get_tokens()));
+  }
+
+  #[test]
+  fn follows_lambdas_after_accepting_in_the_tree() {
+    use super::afn::Outcome;
+
+    // q1 é final e segue por lambda até q2 (também final)
+    let infos = crate::util::file::parse_str("q0 ; q1 q2\nq0 a > q1\nq1 / > q2\nwrd : a");
+    let roots = super::tree::build(&infos, 100);
+    let (_, q1) = &roots[0].children[0];
+    assert_eq!(q1.outcome, Some(Outcome::Accepted));
+    assert_eq!(q1.children.len(), 1);
+    let (symbol, q2) = &q1.children[0];
+    assert_eq!((symbol.as_str(), q2.state.as_str()), ("/", "q2"));
+    assert_eq!(q2.outcome, Some(Outcome::Accepted));
+  }
+
+  #[test]
+  fn simulates_active_state_sets() {
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    // q0 -a-> {q0, q1}, q1 -/-> q2, q2 -b-> q3
+    let node = |character: &str, destination: &str| super::Node {
+      character: character.to_string(),
+      destination: destination.to_string(),
+    };
+    let mut states = HashMap::new();
+    states.insert("q0".to_string(), vec![node("a", "q0"), node("a", "q1")]);
+    states.insert("q1".to_string(), vec![node("/", "q2")]);
+    states.insert("q2".to_string(), vec![node("b", "q3")]);
+    let infos = ParsedFile::new(
+      vec!["q0".to_string()],
+      vec!["q3".to_string()],
+      states,
+      "abb".to_string(),
+    );
+
+    let word = infos.get_tokens();
+    let steps = super::afn::simulate(&infos, &word);
+    // a lambda q1 -/-> q2 dispara junto com o primeiro símbolo
+    assert!(steps[1]
+      .fired
+      .iter()
+      .any(|(origin, node)| origin == "q1" && node.character == "/"));
+    // q0 e q1 morrem ao ler o "b", assim como q3 ao ler o segundo "b"
+    let died: Vec<&String> = steps[2].died.iter().collect();
+    assert_eq!(died, vec!["q0", "q1"]);
+    assert!(steps[3].died.contains("q3"));
+    let sets: Vec<Vec<&String>> = steps.iter().map(|e| e.active.iter().collect()).collect();
+    assert_eq!(
+      sets,
+      vec![
+        vec!["q0"],
+        vec!["q0", "q1", "q2"],
+        vec!["q3"],
+        // esvaziou: a simulação para antes do fim da palavra
+        vec![],
+      ]
+    );
+  }
+
+  #[test]
+  fn hides_hidden_states_in_set_frames() {
+    use crate::util::file::parse_str;
+    use crate::util::Animation;
+
+    // a armadilha fica ativa (e morre) durante a simulação, mas não deve ser desenhada
+    let mut infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nq0 b > trap\nwrd : b a");
+    infos.set_hidden_states(vec!["trap".to_string()]);
+    let frames = std::env::temp_dir().join("afdn_hidden_set_frames");
+    let _ = std::fs::remove_dir_all(&frames);
+    std::fs::create_dir_all(&frames).unwrap();
+
+    assert!(!super::afn::run(
+      &infos,
+      &Animation::automatic(&frames.to_string_lossy())
+    ));
+    let mut count = 0;
+    for frame in std::fs::read_dir(&frames).unwrap() {
+      let content = std::fs::read_to_string(frame.unwrap().path()).unwrap();
+      assert!(!content.contains("trap"), "{}", content);
+      count += 1;
+    }
+    assert!(count > 1);
+  }
+
   #[test]
   fn nodes_are_different() {
     let a = super::Node {
@@ -0,0 +1,329 @@
+use crate::afdn::{afn::LAMBDA, Node, NodeVec};
+use crate::util::file::ParsedFile;
+use std::collections::{BTreeSet, HashMap};
+
+/// Identificador (índice) de um estado do [`Automaton`].
+pub type StateId = usize;
+/// Identificador (índice) de um símbolo do alfabeto do [`Automaton`].
+pub type SymbolId = usize;
+
+/// # Automaton
+/// Modelo tipado do autômato, com os estados e símbolos internados, i.e, identificados por
+/// índices. Os nomes ficam somente nas tabelas `states` e `alphabet` (ambas em ordem).
+///
+/// As transições lambda são representadas pelo símbolo `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Automaton {
+  states: Vec<String>,
+  alphabet: Vec<String>,
+  transitions: Vec<Vec<(Option<SymbolId>, StateId)>>,
+  initial: BTreeSet<StateId>,
+  finals: BTreeSet<StateId>,
+}
+impl Automaton {
+  /// Monta o autômato a partir do arquivo de entrada. O alfabeto é o declarado (ou inferido)
+  /// somado aos símbolos usados nas transições.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::{util::file, Automaton};
+  /// let automaton = Automaton::from_parsed(&file::parse("./inputs/default.txt"));
+  /// ```
+  pub fn from_parsed(infos: &ParsedFile) -> Automaton {
+    let states: Vec<String> = infos.get_all_states().into_iter().collect();
+    let alphabet: Vec<String> = infos.get_full_alphabet().into_iter().collect();
+
+    let state_ids: HashMap<&String, StateId> =
+      states.iter().enumerate().map(|(i, e)| (e, i)).collect();
+    let symbol_ids: HashMap<&String, SymbolId> =
+      alphabet.iter().enumerate().map(|(i, e)| (e, i)).collect();
+
+    let mut transitions: Vec<Vec<(Option<SymbolId>, StateId)>> = vec![Vec::new(); states.len()];
+    for (origin, nodes) in infos.get_states() {
+      let edges = &mut transitions[state_ids[origin]];
+      for node in nodes {
+        let symbol = symbol_ids.get(&node.character).copied();
+        edges.push((symbol, state_ids[&node.destination]));
+      }
+      edges.sort_unstable();
+      edges.dedup();
+    }
+
+    let ids = |names: &[String]| names.iter().map(|e| state_ids[e]).collect();
+    Automaton {
+      initial: ids(infos.get_initial_states()),
+      finals: ids(infos.get_finish_state()),
+      states,
+      alphabet,
+      transitions,
+    }
+  }
+
+  /// Converte de volta para o arquivo de entrada (e.g. para usar os exportadores).
+  pub fn to_parsed(&self, word: &str) -> ParsedFile {
+    let mut states: HashMap<String, NodeVec> = HashMap::new();
+    for (origin, edges) in self.transitions.iter().enumerate() {
+      if edges.is_empty() {
+        continue;
+      }
+      let nodes: NodeVec = edges
+        .iter()
+        .map(|(symbol, destination)| Node {
+          character: match symbol {
+            Some(symbol) => self.alphabet[*symbol].to_string(),
+            None => LAMBDA.to_string(),
+          },
+          destination: self.states[*destination].to_string(),
+        })
+        .collect();
+      states.insert(self.states[origin].to_string(), nodes);
+    }
+
+    let names = |ids: &BTreeSet<StateId>| ids.iter().map(|e| self.states[*e].to_string()).collect();
+    let mut infos = ParsedFile::new(
+      names(&self.initial),
+      names(&self.finals),
+      states,
+      word.to_string(),
+    );
+    infos.set_alphabet(Some(self.alphabet.iter().cloned().collect()));
+    infos
+  }
+
+  /// Nomes dos estados, indexados pelo [`StateId`].
+  pub fn states(&self) -> &[String] {
+    &self.states
+  }
+  pub fn state_id(&self, name: &str) -> Option<StateId> {
+    self.states.binary_search_by(|e| e.as_str().cmp(name)).ok()
+  }
+  pub fn state_name(&self, state: StateId) -> &str {
+    &self.states[state]
+  }
+  /// Símbolos do alfabeto, indexados pelo [`SymbolId`].
+  pub fn alphabet(&self) -> &[String] {
+    &self.alphabet
+  }
+  pub fn symbol_id(&self, symbol: &str) -> Option<SymbolId> {
+    self
+      .alphabet
+      .binary_search_by(|e| e.as_str().cmp(symbol))
+      .ok()
+  }
+  /// Transições que saem do estado, em ordem (as lambdas primeiro).
+  pub fn transitions(&self, state: StateId) -> &[(Option<SymbolId>, StateId)] {
+    &self.transitions[state]
+  }
+  pub fn initial_states(&self) -> &BTreeSet<StateId> {
+    &self.initial
+  }
+  pub fn final_states(&self) -> &BTreeSet<StateId> {
+    &self.finals
+  }
+  pub fn is_final(&self, state: StateId) -> bool {
+    self.finals.contains(&state)
+  }
+
+  /// Um único estado inicial, nenhuma transição lambda e nenhum símbolo repetido por estado.
+  pub fn is_deterministic(&self) -> bool {
+    self.initial.len() == 1
+      && self.transitions.iter().all(|edges| {
+        edges.iter().all(|e| e.0.is_some()) && edges.windows(2).all(|pair| pair[0].0 != pair[1].0)
+      })
+  }
+
+  /// Fecho lambda do conjunto de estados.
+  pub fn closure(&self, mut states: BTreeSet<StateId>) -> BTreeSet<StateId> {
+    let mut stack: Vec<StateId> = states.iter().copied().collect();
+    while let Some(state) = stack.pop() {
+      for (_, destination) in self.transitions[state].iter().filter(|e| e.0.is_none()) {
+        if states.insert(*destination) {
+          stack.push(*destination);
+        }
+      }
+    }
+    states
+  }
+
+  /// Destino da transição do estado com o símbolo (a primeira, caso existam várias), como em um
+  /// AFD.
+  pub fn next(&self, state: StateId, symbol: SymbolId) -> Option<StateId> {
+    self.transitions[state]
+      .iter()
+      .find(|e| e.0 == Some(symbol))
+      .map(|e| e.1)
+  }
+
+  /// Estados alcançáveis a partir de `states` por quaisquer transições (inclusive lambda).
+  pub fn reachable(&self, states: &BTreeSet<StateId>) -> BTreeSet<StateId> {
+    let mut reached = states.clone();
+    let mut stack: Vec<StateId> = states.iter().copied().collect();
+    while let Some(state) = stack.pop() {
+      for (_, destination) in &self.transitions[state] {
+        if reached.insert(*destination) {
+          stack.push(*destination);
+        }
+      }
+    }
+    reached
+  }
+
+  /// Estados a partir dos quais é possível atingir um estado final (busca no grafo invertido).
+  pub fn co_reachable(&self) -> BTreeSet<StateId> {
+    let mut reverse: Vec<Vec<StateId>> = vec![Vec::new(); self.states.len()];
+    for (origin, edges) in self.transitions.iter().enumerate() {
+      for (_, destination) in edges {
+        reverse[*destination].push(origin);
+      }
+    }
+
+    let mut reached = self.finals.clone();
+    let mut stack: Vec<StateId> = self.finals.iter().copied().collect();
+    while let Some(state) = stack.pop() {
+      for origin in &reverse[state] {
+        if reached.insert(*origin) {
+          stack.push(*origin);
+        }
+      }
+    }
+    reached
+  }
+
+  /// Estados alcançados a partir de `states` lendo `symbol` (já com o fecho lambda).
+  pub fn step(&self, states: &BTreeSet<StateId>, symbol: SymbolId) -> BTreeSet<StateId> {
+    let next: BTreeSet<StateId> = states
+      .iter()
+      .flat_map(|state| self.transitions[*state].iter())
+      .filter(|e| e.0 == Some(symbol))
+      .map(|e| e.1)
+      .collect();
+    self.closure(next)
+  }
+}
+
+/// # Simulator
+/// Simulação passo a passo (por conjuntos de estados) de um [`Automaton`], servindo tanto para
+/// AFDs quanto para AFNs.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::{parsers::parse_str, Automaton, Simulator};
+/// # let automaton = Automaton::from_parsed(&parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a"));
+/// let mut simulator = Simulator::new(&automaton);
+/// simulator.feed("a");
+/// println!("{:?} {}", simulator.active(), simulator.is_accepting());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulator<'a> {
+  automaton: &'a Automaton,
+  active: BTreeSet<StateId>,
+  consumed: usize,
+}
+impl<'a> Simulator<'a> {
+  pub fn new(automaton: &'a Automaton) -> Simulator<'a> {
+    Simulator {
+      automaton,
+      active: automaton.closure(automaton.initial.clone()),
+      consumed: 0,
+    }
+  }
+
+  /// Volta para os estados iniciais.
+  pub fn reset(&mut self) {
+    *self = Simulator::new(self.automaton);
+  }
+
+  /// Consome um símbolo. Um símbolo fora do alfabeto trava a simulação (nenhum estado ativo) e,
+  /// depois de travada, os símbolos seguintes não são mais consumidos (como no [`run`](Self::run)).
+  pub fn feed(&mut self, symbol: &str) {
+    if self.is_stuck() {
+      return;
+    }
+    self.active = match self.automaton.symbol_id(symbol) {
+      Some(symbol) => self.automaton.step(&self.active, symbol),
+      None => BTreeSet::new(),
+    };
+    self.consumed += 1;
+  }
+
+  /// Consome todos os símbolos da palavra, retornando se ela foi aceita.
+  pub fn run(&mut self, word: &[String]) -> bool {
+    for symbol in word {
+      if self.is_stuck() {
+        break;
+      }
+      self.feed(symbol);
+    }
+    self.is_accepting()
+  }
+
+  pub fn active(&self) -> &BTreeSet<StateId> {
+    &self.active
+  }
+  /// Quantidade de símbolos consumidos.
+  pub fn consumed(&self) -> usize {
+    self.consumed
+  }
+  pub fn is_accepting(&self) -> bool {
+    self.active.iter().any(|e| self.automaton.is_final(*e))
+  }
+  pub fn is_stuck(&self) -> bool {
+    self.active.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Automaton, Simulator};
+  use crate::util::file;
+
+  #[test]
+  fn interns_states_and_symbols() {
+    let infos = file::parse_str("q0 ; q2\nq0 a > q1\nq0 / > q2\nq1 b > q2\nq2 a > q2\nwrd : ab");
+    let automaton = Automaton::from_parsed(&infos);
+    assert_eq!(automaton.states(), &["q0", "q1", "q2"]);
+    assert_eq!(automaton.alphabet(), &["a", "b"]);
+    assert_eq!(automaton.transitions(0), &[(None, 2), (Some(0), 1)]);
+    assert!(!automaton.is_deterministic());
+    assert_eq!(
+      file::serialize(&automaton.to_parsed("ab")),
+      "q0 ; q2\nalf : a b\nq0 / > q2\nq0 a > q1\nq1 b > q2\nq2 a > q2\nwrd : ab"
+    );
+  }
+
+  #[test]
+  fn simulates_step_by_step() {
+    let infos = file::parse_str("q0 ; q2\nq0 a > q1\nq0 / > q2\nq1 b > q2\nq2 a > q2\nwrd : ab");
+    let automaton = Automaton::from_parsed(&infos);
+    let mut simulator = Simulator::new(&automaton);
+    assert!(simulator.is_accepting());
+
+    simulator.feed("a");
+    assert_eq!(simulator.active().len(), 2);
+    assert!(simulator.run(&["b".to_string()]));
+    assert_eq!(simulator.consumed(), 2);
+
+    simulator.feed("c");
+    assert!(simulator.is_stuck());
+    simulator.reset();
+    assert!(!simulator.run(&["b".to_string()]));
+  }
+
+  #[test]
+  fn feeds_and_runs_alike_on_unknown_symbols() {
+    let infos = file::parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+    let automaton = Automaton::from_parsed(&infos);
+    let word: Vec<String> = ["a", "c", "a", "a"].iter().map(|e| e.to_string()).collect();
+
+    let mut fed = Simulator::new(&automaton);
+    for symbol in &word {
+      fed.feed(symbol);
+    }
+    let mut ran = Simulator::new(&automaton);
+    assert_eq!(fed.is_accepting(), ran.run(&word));
+    assert_eq!(fed.consumed(), ran.consumed());
+    assert_eq!(fed.consumed(), 2);
+  }
+}
@@ -0,0 +1,294 @@
+use crate::afdn::{afd, afn, afn::LAMBDA};
+use crate::automaton::Automaton;
+use crate::compiled::Compiled;
+use crate::util::file::{self, ParsedFile};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+
+/// Verifica se o autômato aceita a palavra, usando o motor adequado (AFD ou AFN).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::check::accepts;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// let aceita: bool = accepts(&infos, &["a".to_string(), "b".to_string()]);
+/// ```
+pub fn accepts(infos: &ParsedFile, word: &[String]) -> bool {
+  if infos.is_afd() {
+    afd::accepts(infos, word)
+  } else {
+    afn::accepts(infos, word)
+  }
+}
+
+/// # Reference
+/// Referência contra a qual o autômato será comparado.
+pub enum Reference {
+  /// Expressão regular sobre a palavra (com os símbolos concatenados). É sempre ancorada, i.e,
+  /// deve casar com a palavra inteira.
+  Regex(Regex),
+  /// Um segundo autômato (e.g. o gabarito), já compilado.
+  Automaton(Box<(Automaton, Compiled)>),
+}
+impl Reference {
+  /// Cria uma referência a partir de uma expressão regular.
+  pub fn regex(expression: &str) -> Reference {
+    match Regex::new(&format!("^(?:{})$", expression)) {
+      Ok(regex) => Reference::Regex(regex),
+      Err(err) => panic!("Expressão regular inválida: {}", err),
+    }
+  }
+
+  /// Cria uma referência a partir de outro arquivo de entrada.
+  pub fn file(filepath: &str) -> Reference {
+    let automaton = Automaton::from_parsed(&file::parse(filepath));
+    let compiled = Compiled::new(&automaton);
+    Reference::Automaton(Box::new((automaton, compiled)))
+  }
+
+  pub fn accepts(&self, word: &[String]) -> bool {
+    match self {
+      Reference::Regex(regex) => regex.is_match(&word.join("")),
+      Reference::Automaton(reference) => {
+        let (automaton, compiled) = reference.as_ref();
+        compiled.accepts(word.iter().map(|e| automaton.symbol_id(e)))
+      }
+    }
+  }
+}
+
+/// Gera uma palavra uniformemente: primeiro sorteia o tamanho (entre 0 e `max_len`) e, depois,
+/// cada um dos símbolos.
+pub fn uniform_word<R: Rng>(rng: &mut R, alphabet: &[String], max_len: usize) -> Vec<String> {
+  let len = rng.gen_range(0..=max_len);
+  (0..len)
+    .filter_map(|_| alphabet.choose(rng).cloned())
+    .collect()
+}
+
+/// Gera uma palavra por um passeio aleatório no grafo de transições, partindo de um estado
+/// inicial. Ao passar por um estado final, o passeio para com probabilidade `1/3`, o que favorece
+/// palavras aceitas.
+pub fn walk_word<R: Rng>(rng: &mut R, infos: &ParsedFile, max_len: usize) -> Vec<String> {
+  let mut word: Vec<String> = Vec::new();
+  let mut state = match infos.get_initial_states().choose(rng) {
+    Some(state) => state.to_string(),
+    None => return word,
+  };
+
+  // limita também os passos lambda, para não ficar preso em laços
+  for _ in 0..(2 * max_len + 1) {
+    if infos.get_finish_state().contains(&state) && rng.gen_ratio(1, 3) {
+      break;
+    }
+    let next = match infos.get_states().get(&state).and_then(|e| e.choose(rng)) {
+      Some(next) => next,
+      None => break,
+    };
+    if next.character != LAMBDA {
+      if word.len() == max_len {
+        break;
+      }
+      word.push(next.character.to_string());
+    }
+    state = next.destination.to_string();
+  }
+  word
+}
+
+/// # Report
+/// Resultado do teste com palavras aleatórias.
+#[derive(Debug)]
+pub struct Report {
+  pub tested: usize,
+  /// Palavras (já reduzidas) em que o autômato e a referência discordam (e o veredito do
+  /// autômato), em ordem shortlex e sem repetições.
+  pub failures: Vec<(Vec<String>, bool)>,
+}
+
+/// Compara o autômato com a referência em `count` palavras aleatórias: metade gerada
+/// uniformemente e metade por passeios aleatórios no grafo (que tendem a ser aceitas). Cada
+/// divergência é reduzida (veja [`shrink`]) antes de ser reportada.
+///
+/// ## Arguments
+///
+/// * `infos` - O arquivo de entrada lido e mapeado.
+/// * `reference` - Expressão regular ou autômato de referência.
+/// * `count` - Quantidade de palavras testadas.
+/// * `max_len` - Tamanho máximo das palavras.
+/// * `rng` - Gerador de números aleatórios (permite fixar a semente).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::check::{random_test, Reference};
+/// # use rand::SeedableRng;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+/// let report = random_test(&infos, &Reference::regex("(a|b)*ab"), 1000, 10, &mut rng);
+/// ```
+pub fn random_test<R: Rng>(
+  infos: &ParsedFile,
+  reference: &Reference,
+  count: usize,
+  max_len: usize,
+  rng: &mut R,
+) -> Report {
+  let alphabet: Vec<String> = infos.get_alphabet().into_iter().collect();
+  let diverges = |word: &[String]| self::accepts(infos, word) != reference.accepts(word);
+
+  let mut failures: Vec<(Vec<String>, bool)> = Vec::new();
+  for i in 0..count {
+    let word = if i % 2 == 0 {
+      self::uniform_word(rng, &alphabet, max_len)
+    } else {
+      self::walk_word(rng, infos, max_len)
+    };
+
+    if diverges(&word) {
+      debug!("Mismatch on {:?}", &word);
+      let word = self::shrink(word, &diverges);
+      let verdict = self::accepts(infos, &word);
+      failures.push((word, verdict));
+    }
+  }
+
+  // as menores palavras primeiro (shortlex)
+  failures.sort_by(|a, b| (a.0.len(), &a.0).cmp(&(b.0.len(), &b.0)));
+  failures.dedup_by(|a, b| a.0 == b.0);
+
+  Report {
+    tested: count,
+    failures,
+  }
+}
+
+/// Reduz uma palavra que diverge: remove um símbolo por vez enquanto a palavra continuar
+/// divergindo, até que nenhuma remoção mantenha a divergência.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::check::shrink;
+/// let word: Vec<String> = vec!["a".into(), "b".into(), "a".into()];
+/// // diverge enquanto houver um "b"
+/// let shrunk = shrink(word, &|word: &[String]| word.contains(&"b".to_string()));
+/// assert_eq!(shrunk, vec!["b".to_string()]);
+/// ```
+pub fn shrink<F: Fn(&[String]) -> bool>(mut word: Vec<String>, diverges: &F) -> Vec<String> {
+  let mut i = 0;
+  while i < word.len() {
+    let mut candidate = word.clone();
+    candidate.remove(i);
+    if diverges(&candidate) {
+      // recomeça: uma remoção pode liberar outras antes da posição atual
+      word = candidate;
+      i = 0;
+    } else {
+      i += 1;
+    }
+  }
+  word
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Reference;
+  use crate::afdn::Node;
+  use crate::automaton::Automaton;
+  use crate::compiled::Compiled;
+  use crate::util::file::ParsedFile;
+  use rand::rngs::StdRng;
+  use rand::SeedableRng;
+  use std::collections::HashMap;
+
+  // palavras sobre {a, b} que terminam em "b"
+  fn ends_with_b() -> ParsedFile {
+    let node = |character: &str, destination: &str| Node {
+      character: character.to_string(),
+      destination: destination.to_string(),
+    };
+    let mut states = HashMap::new();
+    states.insert("q0".to_string(), vec![node("a", "q0"), node("b", "q1")]);
+    states.insert("q1".to_string(), vec![node("a", "q0"), node("b", "q1")]);
+    ParsedFile::new(
+      vec!["q0".to_string()],
+      vec!["q1".to_string()],
+      states,
+      String::new(),
+    )
+  }
+
+  #[test]
+  fn agrees_with_equivalent_regex() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let report = super::random_test(
+      &ends_with_b(),
+      &Reference::regex("(a|b)*b"),
+      500,
+      8,
+      &mut rng,
+    );
+    assert_eq!(report.tested, 500);
+    assert!(report.failures.is_empty(), "{:?}", report.failures);
+  }
+
+  #[test]
+  fn reports_minimal_failing_words() {
+    let mut rng = StdRng::seed_from_u64(42);
+    // a referência também aceita a palavra vazia
+    let report = super::random_test(
+      &ends_with_b(),
+      &Reference::regex("((a|b)*b)?"),
+      500,
+      8,
+      &mut rng,
+    );
+    assert_eq!(report.failures[0], (Vec::new(), false));
+    assert_eq!(report.failures.len(), 1);
+  }
+
+  #[test]
+  fn shrinks_failing_words() {
+    let mut rng = StdRng::seed_from_u64(42);
+    // a referência rejeita as palavras com "aa" (e aceita a vazia)
+    let report = super::random_test(
+      &ends_with_b(),
+      &Reference::regex("(b|ab)*"),
+      500,
+      8,
+      &mut rng,
+    );
+    let word = |w: &str| w.chars().map(|c| c.to_string()).collect::<Vec<String>>();
+    assert_eq!(
+      report.failures,
+      vec![(Vec::new(), false), (word("aab"), true)]
+    );
+  }
+
+  #[test]
+  fn compiled_agrees_with_the_engines() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let nfa = crate::util::file::parse_str(
+      "q0 ; q2\nq0 a > q0\nq0 b > q0\nq0 a > q1\nq1 / > q2\nq2 b > q2\nwrd : a",
+    );
+    for infos in [ends_with_b(), nfa].iter() {
+      let automaton = Automaton::from_parsed(infos);
+      let compiled = Compiled::new(&automaton);
+      let alphabet: Vec<String> = infos.get_alphabet().into_iter().collect();
+      for _ in 0..500 {
+        let word = super::uniform_word(&mut rng, &alphabet, 8);
+        assert_eq!(
+          compiled.accepts(word.iter().map(|e| automaton.symbol_id(e))),
+          super::accepts(infos, &word),
+          "{:?}",
+          &word
+        );
+      }
+    }
+  }
+}
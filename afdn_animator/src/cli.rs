@@ -0,0 +1,409 @@
+use crate::compiled::Compiled;
+use crate::util::{file, Animation};
+use crate::{afdn, check, convert, dot, exercise, generate, language, stream, Automaton};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Executa o programa com os argumentos da linha de comando (sem o nome do executável): o arquivo
+/// de entrada seguido das opções ou um dos modos que não leem um arquivo (`--gerar` e
+/// `--exercicio`). As opções estão descritas no README.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use afdn_animator::cli::run;
+/// let args: Vec<String> = std::env::args().skip(1).collect();
+/// run(&args);
+/// ```
+pub fn run(args: &[String]) {
+  if args.is_empty() {
+    panic!("Você deve passar um arquivo de entrada");
+  }
+  if args[0].starts_with("--gerar") {
+    // gera um autômato aleatório (no formato de entrada) ao invés de ler um arquivo
+    generate(args);
+    return;
+  }
+  if args[0].starts_with("--exercicio") {
+    create_exercise(args);
+    return;
+  }
+  let filename: &String = &args[0];
+  debug!("Filename: {:#?}", filename);
+
+  // opções adicionais (após o arquivo de entrada)
+  let options: &[String] = &args[1..];
+  debug!("Options: {:#?}", options);
+
+  // parsed informations
+  let mut infos = file::parse(filename);
+  debug!("{:#?}", &infos);
+
+  // valida as transições e a palavra contra o alfabeto
+  let validation = file::validate(&infos);
+  for message in validation.messages() {
+    eprintln!("Aviso: {}", message);
+  }
+
+  if options.iter().any(|e| e == "--completar") {
+    // completa o AFD parcial com um estado armadilha
+    let trap = option_value(options, "--armadilha").unwrap_or(convert::complete::TRAP);
+    let hide_trap = options.iter().any(|e| e == "--ocultar-armadilha");
+    infos = convert::complete::complete(&infos, trap, hide_trap);
+    debug!("Completed: {:#?}", &infos);
+  }
+
+  if options.iter().any(|e| e == "--sem-lambda") {
+    // gera o AFN sem transições lambda (e os grafos de antes/depois)
+    let lambda_free = convert::lambda::remove(&infos);
+    convert::lambda::save_dot_files(&infos, &lambda_free, "./dot");
+    println!("{}", serialize(&lambda_free, options));
+  } else if let Some(source) = options
+    .iter()
+    .find(|e| *e == "--fluxo" || e.starts_with("--fluxo="))
+  {
+    self::stream_word(&infos, source, options);
+  } else if options.iter().any(|e| e == "--canonico") {
+    // renomeia os estados (q0, q1, ...) na ordem da busca em largura
+    println!(
+      "{}",
+      serialize(&convert::canonical::canonicalize(&infos), options)
+    );
+  } else if let Some(other) = option_value(options, "--isomorfo") {
+    self::isomorphism(&infos, other);
+  } else if let Some(max_count) = option_limit(options, "--enumerar", 20) {
+    self::enumerate(&infos, max_count, options);
+  } else if options.iter().any(|e| e == "--menores") {
+    self::shortest(&infos);
+  } else if let Some(key) = option_value(options, "--corrigir") {
+    self::grade(&infos, key);
+  } else if let Some(count) = option_limit(options, "--testar", 1000) {
+    self::random_test(&infos, count, options);
+  } else if !validation.is_word_valid() {
+    // a palavra possui símbolos fora do alfabeto, nem precisa executar
+    eprintln!("Palavra {} inválida para o alfabeto", infos.get_word());
+    println!("Erro 😔");
+  } else if let Some(limit) = option_limit(options, "--caminhos", 10) {
+    self::paths(&infos, limit, options);
+  } else if let Some(max_nodes) = option_limit(options, "--arvore", 500) {
+    self::tree(&infos, max_nodes);
+  } else {
+    self::animate(&infos);
+  }
+}
+
+/// Exibe o resultado final da execução.
+fn verdict(accepted: bool) {
+  if accepted {
+    println!("Sucesso 😊");
+  } else {
+    println!("Erro 😔");
+  }
+}
+
+/// Exibe uma palavra, com `ε` para a palavra vazia.
+fn shown(word: &[String]) -> String {
+  match file::join_tokens(word) {
+    word if word.is_empty() => "ε".to_string(),
+    word => word,
+  }
+}
+
+/// Lê a palavra em blocos de um arquivo (`--fluxo=<arquivo>`) ou da entrada padrão, ignorando a
+/// linha `wrd`.
+fn stream_word(infos: &file::ParsedFile, source: &str, options: &[String]) {
+  let automaton = Automaton::from_parsed(infos);
+  let compiled = Compiled::new(&automaton);
+  let show_positions = options.iter().any(|e| e == "--posicoes");
+  let on_accepting = |position: usize| {
+    if show_positions {
+      println!("Estado final após {} símbolos", position);
+    }
+  };
+
+  let report = match source.strip_prefix("--fluxo=") {
+    Some(path) if path != "-" => {
+      let input = std::fs::File::open(path).expect("Houve um problema ao ler o arquivo");
+      stream::run(
+        &automaton,
+        &compiled,
+        std::io::BufReader::new(input),
+        on_accepting,
+      )
+    }
+    _ => stream::run(&automaton, &compiled, std::io::stdin(), on_accepting),
+  }
+  .expect("Houve um problema ao ler a palavra");
+
+  println!("{} símbolos lidos", report.consumed);
+  if let Some(position) = report.stuck_at {
+    println!("Travou no símbolo {}", position);
+  }
+  self::verdict(report.accepted);
+}
+
+/// Compara a estrutura com outro AFD (`--isomorfo=<arquivo>`), exibindo o mapeamento entre os
+/// estados.
+fn isomorphism(infos: &file::ParsedFile, other: &str) {
+  let other = file::parse(other);
+  match convert::canonical::isomorphism(infos, &other) {
+    Ok(mapping) => {
+      for (left, right) in mapping {
+        println!("{} ↔ {}", left, right);
+      }
+      self::verdict(true);
+    }
+    Err(difference) => {
+      println!("{}", difference);
+      self::verdict(false);
+    }
+  }
+}
+
+/// Lista as palavras aceitas em ordem shortlex e classifica a linguagem.
+fn enumerate(infos: &file::ParsedFile, max_count: usize, options: &[String]) {
+  let max_len = option_limit(options, "--tamanho-max", 10).unwrap_or(10);
+  for word in language::enumerate(infos, max_len, max_count) {
+    println!("{}", self::shown(&word));
+  }
+  match language::size(infos) {
+    language::Size::Empty => println!("Linguagem vazia"),
+    language::Size::Finite(n) => println!("Linguagem finita ({} palavras)", n),
+    language::Size::Infinite => println!("Linguagem infinita"),
+  }
+}
+
+/// Menor palavra aceita e menor rejeitada, animando cada uma automaticamente.
+fn shortest(infos: &file::ParsedFile) {
+  let shortest = [
+    ("aceita", language::shortest_accepted(infos)),
+    ("rejeitada", language::shortest_rejected(infos)),
+  ];
+  for (kind, word) in shortest.iter() {
+    let word = match word {
+      Some(word) => word,
+      None => {
+        println!("Não existe palavra {}", kind);
+        continue;
+      }
+    };
+    println!("Menor palavra {}: {}", kind, self::shown(word));
+
+    let dir = format!("./dot/menor_{}", kind);
+    std::fs::create_dir_all(&dir).expect("Não foi possível criar o diretório");
+    let mut animated = infos.clone();
+    animated.set_word(file::join_tokens(word));
+    if animated.is_afd() {
+      afdn::afd::run(&animated, &Animation::automatic(&dir));
+    } else {
+      afdn::afn::run(&animated, &Animation::automatic(&dir));
+    }
+  }
+}
+
+/// Corrige a submissão (o arquivo de entrada) pela equivalência com a chave do exercício.
+fn grade(infos: &file::ParsedFile, key: &str) {
+  let key = file::parse(key);
+  match exercise::grade(infos, &key) {
+    exercise::Grade::Correct => self::verdict(true),
+    exercise::Grade::Wrong(word, accepted) => {
+      let verdict = if accepted { "aceita" } else { "rejeita" };
+      println!(
+        "O autômato {} {}, mas a chave não",
+        verdict,
+        self::shown(&word)
+      );
+      self::verdict(false);
+    }
+  }
+}
+
+/// Compara o autômato com uma referência (regex ou outro arquivo) em palavras aleatórias.
+fn random_test(infos: &file::ParsedFile, count: usize, options: &[String]) {
+  let reference = match (
+    option_value(options, "--regex"),
+    option_value(options, "--referencia"),
+  ) {
+    (Some(expression), _) => check::Reference::regex(expression),
+    (None, Some(filepath)) => check::Reference::file(filepath),
+    (None, None) => {
+      panic!("Informe a referência com --regex=<expr> ou --referencia=<arquivo>")
+    }
+  };
+  let max_len = option_limit(options, "--tamanho-max", 10).unwrap_or(10);
+  let mut rng = seeded_rng(options);
+
+  let report = check::random_test(infos, &reference, count, max_len, &mut rng);
+  println!(
+    "{} palavras testadas, {} divergências",
+    report.tested,
+    report.failures.len()
+  );
+  for (word, verdict) in report.failures.iter().take(5) {
+    let verdict = if *verdict { "aceita" } else { "rejeita" };
+    println!(
+      "  {}: o autômato {}, a referência não",
+      self::shown(word),
+      verdict
+    );
+  }
+  self::verdict(report.failures.is_empty());
+}
+
+/// Lista todos os caminhos (computações) do AFN, gerando uma série de quadros para cada um.
+fn paths(infos: &file::ParsedFile, limit: usize, options: &[String]) {
+  let include_rejecting = options.iter().any(|e| e == "--rejeitados");
+  let computations = afdn::afn::all_paths(infos, limit, include_rejecting);
+  for (k, computation) in computations.iter().enumerate() {
+    println!(
+      "#{} [{:?}] {}",
+      k,
+      computation.outcome,
+      computation.describe()
+    );
+  }
+  afdn::afn::save_paths(infos, &computations, "./dot");
+
+  self::verdict(
+    computations
+      .iter()
+      .any(|e| e.outcome == afdn::afn::Outcome::Accepted),
+  );
+}
+
+/// Gera a árvore de computação (dot e svg) da palavra.
+fn tree(infos: &file::ParsedFile, max_nodes: usize) {
+  let roots = afdn::tree::build(infos, max_nodes);
+  afdn::tree::save_dot_file(infos, &roots, "./dot/arvore.dot");
+  dot::render("./dot/arvore.dot", "svg");
+  self::verdict(roots.iter().any(|e| e.accepting));
+}
+
+/// Anima a palavra, quadro a quadro (com o menu), com o motor adequado (AFD ou AFN).
+fn animate(infos: &file::ParsedFile) {
+  if infos.is_afd() {
+    debug!("IT is an AFD");
+    // é um afd, então roda o padrão
+    self::verdict(afdn::afd::run(infos, &Animation::default()));
+  } else {
+    // é um afn, então roda outro algoritmo (recursivo)
+    debug!("IT is an AFN");
+    self::verdict(afdn::afn::run(infos, &Animation::default()));
+  }
+}
+
+/// Configuração do gerador de autômatos aleatórios a partir das opções.
+fn generator_config(options: &[String]) -> generate::Config {
+  let default = generate::Config::default();
+  let ratio = |name: &str, default: f64| match option_value(options, name) {
+    Some(value) => match value.parse::<f64>() {
+      Ok(ratio) if (0.0..=1.0).contains(&ratio) => ratio,
+      _ => panic!(
+        "Probabilidade inválida para {}: {} (deve estar entre 0 e 1)",
+        name, value
+      ),
+    },
+    None => default,
+  };
+  generate::Config {
+    states: option_limit(options, "--estados", default.states).unwrap_or(default.states),
+    alphabet: match option_value(options, "--alfabeto") {
+      Some(symbols) => generate::parse_alphabet(symbols).unwrap_or_else(|err| panic!("{}", err)),
+      None => default.alphabet,
+    },
+    deterministic: !options.iter().any(|e| e == "--afn"),
+    density: ratio("--densidade", default.density),
+    lambda: ratio("--lambda", default.lambda),
+    finals: option_limit(options, "--finais", default.finals).unwrap_or(default.finals),
+    word_len: option_limit(options, "--tamanho-max", default.word_len).unwrap_or(default.word_len),
+  }
+}
+
+/// Gera um autômato aleatório conforme as opções e o salva em `--gerar=<arquivo>` (ou o exibe na
+/// saída padrão).
+fn generate(options: &[String]) {
+  let config = generator_config(options);
+  let mut rng = seeded_rng(options);
+
+  let output = serialize(&generate::generate(&config, &mut rng), options);
+  match option_value(options, "--gerar") {
+    Some(filename) => match std::fs::write(filename, output) {
+      Ok(_) => println!("Autômato salvo em {}", filename),
+      Err(err) => panic!("{}", err),
+    },
+    None => println!("{}", output),
+  }
+}
+
+/// Gera um exercício (enunciado, máquina dada, gabarito e chave de correção) no diretório
+/// `--exercicio=<dir>` (padrão `./exercicio`).
+fn create_exercise(options: &[String]) {
+  let config = generator_config(options);
+  let mut rng = seeded_rng(options);
+  let pattern: Option<Vec<String>> = option_value(options, "--padrao")
+    .map(|e| file::tokenize(e, &config.alphabet.iter().cloned().collect()));
+
+  let kind = match (option_value(options, "--tipo"), pattern) {
+        (None, _) => exercise::random_kind(&mut rng, &config.alphabet),
+        (Some("terminadas"), Some(pattern)) => exercise::Kind::EndsWith(pattern),
+        (Some("contem"), Some(pattern)) => exercise::Kind::Contains(pattern),
+        (Some("determinizar"), _) => exercise::Kind::Determinize,
+        (Some("minimizar"), _) => exercise::Kind::Minimize,
+        (Some(kind), _) => panic!(
+            "Tipo de exercício inválido: {}. Use terminadas/contem (com --padrao=<palavra>), determinizar ou minimizar",
+            kind
+        ),
+    };
+
+  let dir = option_value(options, "--exercicio").unwrap_or("./exercicio");
+  let exercise = match exercise::create(kind, &config, &mut rng) {
+    Ok(exercise) => exercise,
+    Err(err) => {
+      eprintln!("{}", err);
+      println!("Erro 😔");
+      return;
+    }
+  };
+  exercise::save(&exercise, dir);
+  println!("{}", exercise.statement);
+  println!("Exercício salvo em {}", dir);
+}
+
+/// Escreve o autômato no formato de entrada, agrupando as transições entre os mesmos estados em
+/// uma única linha com `--compacto`.
+fn serialize(infos: &file::ParsedFile, options: &[String]) -> String {
+  if options.iter().any(|e| e == "--compacto") {
+    file::serialize_compact(infos)
+  } else {
+    file::serialize(infos)
+  }
+}
+
+/// Gerador de números aleatórios, com a semente de `--semente=<n>` (se informada).
+fn seeded_rng(options: &[String]) -> StdRng {
+  match option_value(options, "--semente") {
+    Some(seed) => StdRng::seed_from_u64(seed.parse().expect("Semente inválida")),
+    None => StdRng::from_entropy(),
+  }
+}
+
+/// Obtém o valor de uma opção no formato `--nome=valor`.
+fn option_value<'a>(options: &'a [String], name: &str) -> Option<&'a str> {
+  let prefix = format!("{}=", name);
+  options
+    .iter()
+    .find(|e| e.starts_with(&prefix))
+    .map(|e| &e[prefix.len()..])
+}
+
+/// Obtém o limite de uma opção no formato `--nome` (usa o `default`) ou `--nome=<limite>`.
+fn option_limit(options: &[String], name: &str, default: usize) -> Option<usize> {
+  if options.iter().any(|e| e == name) {
+    return Some(default);
+  }
+  option_value(options, name).map(|value| {
+    value
+      .parse::<usize>()
+      .unwrap_or_else(|err| panic!("Valor inválido para {}: {}", name, err))
+  })
+}
@@ -0,0 +1,363 @@
+use crate::automaton::{Automaton, StateId, SymbolId};
+
+/// Estado "morto" da tabela do [`Dfa`] (transição ausente).
+const DEAD: StateId = StateId::MAX;
+
+/// Divide a palavra nos símbolos do autômato (mesmas regras do `tokenize`), sem alocar os
+/// símbolos: cada item é o [`SymbolId`] ou `None`, caso o trecho não pertença ao alfabeto.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::automaton::SymbolId;
+/// # use afdn_animator::compiled::Symbols;
+/// # use afdn_animator::{parsers::parse_str, Automaton};
+/// # let automaton = Automaton::from_parsed(&parse_str("q0 ; q1\nq0 10 > q1\nq1 a > q1\nwrd : 10a"));
+/// let ids: Vec<Option<SymbolId>> = Symbols::new(&automaton, "10a").collect();
+/// ```
+pub struct Symbols<'a, 'w> {
+  automaton: &'a Automaton,
+  rest: &'w str,
+  /// Palavra com espaços: cada parte separada por espaço é um símbolo.
+  words: Option<std::str::SplitWhitespace<'w>>,
+  max_len: usize,
+}
+impl<'a, 'w> Symbols<'a, 'w> {
+  pub fn new(automaton: &'a Automaton, word: &'w str) -> Symbols<'a, 'w> {
+    Symbols {
+      automaton,
+      rest: word,
+      words: if word.contains(char::is_whitespace) {
+        Some(word.split_whitespace())
+      } else {
+        None
+      },
+      max_len: automaton
+        .alphabet()
+        .iter()
+        .map(|e| e.len())
+        .max()
+        .unwrap_or(0),
+    }
+  }
+}
+impl<'a, 'w> Iterator for Symbols<'a, 'w> {
+  type Item = Option<SymbolId>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Some(words) = &mut self.words {
+      return words.next().map(|e| self.automaton.symbol_id(e));
+    }
+
+    // longest match: tenta do maior tamanho de símbolo para o menor
+    let first = self.rest.chars().next()?;
+    for len in (1..=self.max_len.min(self.rest.len())).rev() {
+      if !self.rest.is_char_boundary(len) {
+        continue;
+      }
+      if let Some(symbol) = self.automaton.symbol_id(&self.rest[..len]) {
+        self.rest = &self.rest[len..];
+        return Some(Some(symbol));
+      }
+    }
+    self.rest = &self.rest[first.len_utf8()..];
+    Some(None)
+  }
+}
+
+/// # Dfa
+/// AFD compilado em uma tabela densa `estado x símbolo`.
+#[derive(Debug, Clone)]
+pub struct Dfa {
+  symbols: usize,
+  table: Vec<StateId>,
+  start: StateId,
+  finals: Vec<bool>,
+}
+impl Dfa {
+  /// Compila o autômato, caso ele seja determinístico.
+  pub fn new(automaton: &Automaton) -> Option<Dfa> {
+    if !automaton.is_deterministic() {
+      return None;
+    }
+    let symbols = automaton.alphabet().len();
+    let states = automaton.states().len();
+
+    let mut table = vec![DEAD; states * symbols];
+    for state in 0..states {
+      for (symbol, destination) in automaton.transitions(state) {
+        // não há lambda em um AFD
+        table[state * symbols + symbol.unwrap()] = *destination;
+      }
+    }
+    Some(Dfa {
+      symbols,
+      table,
+      start: *automaton.initial_states().iter().next().unwrap(),
+      finals: (0..states).map(|e| automaton.is_final(e)).collect(),
+    })
+  }
+
+  pub fn start(&self) -> StateId {
+    self.start
+  }
+  pub fn is_final(&self, state: StateId) -> bool {
+    self.finals[state]
+  }
+
+  /// Próximo estado, caso exista a transição.
+  pub fn next(&self, state: StateId, symbol: SymbolId) -> Option<StateId> {
+    match self.table[state * self.symbols + symbol] {
+      DEAD => None,
+      next => Some(next),
+    }
+  }
+
+  pub fn accepts<I: IntoIterator<Item = Option<SymbolId>>>(&self, word: I) -> bool {
+    let mut state = self.start;
+    for symbol in word {
+      match symbol.and_then(|symbol| self.next(state, symbol)) {
+        Some(next) => state = next,
+        None => return false,
+      }
+    }
+    self.finals[state]
+  }
+}
+
+/// # Nfa
+/// AFN compilado em listas de adjacência esparsas (formato CSR: os destinos de `(estado, símbolo)`
+/// ficam em `targets[offsets[i]..offsets[i + 1]]`) e com os fechos lambda pré-calculados.
+#[derive(Debug, Clone)]
+pub struct Nfa {
+  symbols: usize,
+  offsets: Vec<usize>,
+  targets: Vec<StateId>,
+  closures: Vec<Vec<StateId>>,
+  start: Vec<StateId>,
+  finals: Vec<bool>,
+}
+impl Nfa {
+  pub fn new(automaton: &Automaton) -> Nfa {
+    let symbols = automaton.alphabet().len();
+    let states = automaton.states().len();
+
+    let mut offsets: Vec<usize> = Vec::with_capacity(states * symbols + 1);
+    let mut targets: Vec<StateId> = Vec::new();
+    for state in 0..states {
+      // as transições estão ordenadas por símbolo (lambda primeiro)
+      let edges = automaton.transitions(state);
+      for symbol in 0..symbols {
+        offsets.push(targets.len());
+        targets.extend(edges.iter().filter(|e| e.0 == Some(symbol)).map(|e| e.1));
+      }
+    }
+    offsets.push(targets.len());
+
+    let closures: Vec<Vec<StateId>> = (0..states)
+      .map(|state| {
+        automaton
+          .closure(std::iter::once(state).collect())
+          .into_iter()
+          .collect()
+      })
+      .collect();
+
+    Nfa {
+      symbols,
+      offsets,
+      targets,
+      closures,
+      start: automaton
+        .closure(automaton.initial_states().clone())
+        .into_iter()
+        .collect(),
+      finals: (0..states).map(|e| automaton.is_final(e)).collect(),
+    }
+  }
+
+  /// Estados ativos no início (fecho lambda dos iniciais).
+  pub fn start(&self) -> &[StateId] {
+    &self.start
+  }
+  pub fn is_final(&self, state: StateId) -> bool {
+    self.finals[state]
+  }
+
+  /// Avança o conjunto `active` lendo o símbolo, escrevendo o resultado (já com o fecho lambda)
+  /// em `next`. `seen` é um vetor auxiliar (um por estado, todo `false`), reaproveitado entre os
+  /// passos para evitar alocações.
+  pub fn step(
+    &self,
+    active: &[StateId],
+    symbol: SymbolId,
+    next: &mut Vec<StateId>,
+    seen: &mut [bool],
+  ) {
+    next.clear();
+    for state in active {
+      let index = state * self.symbols + symbol;
+      for target in &self.targets[self.offsets[index]..self.offsets[index + 1]] {
+        for reached in &self.closures[*target] {
+          if !seen[*reached] {
+            seen[*reached] = true;
+            next.push(*reached);
+          }
+        }
+      }
+    }
+    for state in next.iter() {
+      seen[*state] = false;
+    }
+  }
+
+  pub fn accepts<I: IntoIterator<Item = Option<SymbolId>>>(&self, word: I) -> bool {
+    let mut active = self.start.to_vec();
+    let mut next: Vec<StateId> = Vec::new();
+    let mut seen = vec![false; self.finals.len()];
+    for symbol in word {
+      match symbol {
+        Some(symbol) if !active.is_empty() => {
+          self.step(&active, symbol, &mut next, &mut seen);
+          std::mem::swap(&mut active, &mut next);
+        }
+        _ => return false,
+      }
+    }
+    active.iter().any(|e| self.finals[*e])
+  }
+}
+
+/// # Compiled
+/// Representação compilada do autômato: tabela densa para AFDs e adjacência esparsa para AFNs.
+#[derive(Debug, Clone)]
+pub enum Compiled {
+  Dfa(Dfa),
+  Nfa(Nfa),
+}
+impl Compiled {
+  pub fn new(automaton: &Automaton) -> Compiled {
+    match Dfa::new(automaton) {
+      Some(dfa) => Compiled::Dfa(dfa),
+      None => Compiled::Nfa(Nfa::new(automaton)),
+    }
+  }
+
+  pub fn accepts<I: IntoIterator<Item = Option<SymbolId>>>(&self, word: I) -> bool {
+    match self {
+      Compiled::Dfa(dfa) => dfa.accepts(word),
+      Compiled::Nfa(nfa) => nfa.accepts(word),
+    }
+  }
+}
+
+/// # Runner
+/// Simulação incremental sobre o autômato compilado: consome um símbolo por vez, sem guardar a
+/// palavra (veja o módulo `stream`).
+#[derive(Debug, Clone)]
+pub struct Runner<'c> {
+  compiled: &'c Compiled,
+  /// Estados ativos (no AFD, no máximo um).
+  active: Vec<StateId>,
+  next: Vec<StateId>,
+  seen: Vec<bool>,
+}
+impl<'c> Runner<'c> {
+  pub fn new(compiled: &'c Compiled) -> Runner<'c> {
+    let (active, states) = match compiled {
+      Compiled::Dfa(dfa) => (vec![dfa.start], dfa.finals.len()),
+      Compiled::Nfa(nfa) => (nfa.start.to_vec(), nfa.finals.len()),
+    };
+    Runner {
+      compiled,
+      active,
+      next: Vec::new(),
+      seen: vec![false; states],
+    }
+  }
+
+  /// Consome um símbolo (`None` = fora do alfabeto, o que trava a simulação).
+  pub fn feed(&mut self, symbol: Option<SymbolId>) {
+    let symbol = match symbol {
+      Some(symbol) if !self.active.is_empty() => symbol,
+      _ => return self.active.clear(),
+    };
+    match self.compiled {
+      Compiled::Dfa(dfa) => match dfa.next(self.active[0], symbol) {
+        Some(next) => self.active[0] = next,
+        None => self.active.clear(),
+      },
+      Compiled::Nfa(nfa) => {
+        nfa.step(&self.active, symbol, &mut self.next, &mut self.seen);
+        std::mem::swap(&mut self.active, &mut self.next);
+      }
+    }
+  }
+
+  pub fn active(&self) -> &[StateId] {
+    &self.active
+  }
+  pub fn is_accepting(&self) -> bool {
+    let finals = match self.compiled {
+      Compiled::Dfa(dfa) => &dfa.finals,
+      Compiled::Nfa(nfa) => &nfa.finals,
+    };
+    self.active.iter().any(|e| finals[*e])
+  }
+  pub fn is_stuck(&self) -> bool {
+    self.active.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Compiled, Dfa, Nfa, Symbols};
+  use crate::automaton::Automaton;
+  use crate::util::file;
+
+  #[test]
+  fn splits_multi_character_symbols() {
+    let infos = file::parse_str("q0 ; q0\nalf : 1 10 a\nq0 10 > q0\nq0 a > q0\nq0 1 > q0\nwrd : ");
+    let automaton = Automaton::from_parsed(&infos);
+    let ids: Vec<Option<usize>> = Symbols::new(&automaton, "10a1x").collect();
+    assert_eq!(ids, vec![Some(1), Some(2), Some(0), None]);
+    let ids: Vec<Option<usize>> = Symbols::new(&automaton, "1 0 10").collect();
+    assert_eq!(ids, vec![Some(0), None, Some(1)]);
+  }
+
+  #[test]
+  fn runs_long_words_on_large_automata() {
+    // ciclo com 20000 estados: aceita as palavras cujo tamanho é múltiplo de 20000
+    let size = 20000;
+    let mut content = "s0 ; s0\n".to_string();
+    for i in 0..size {
+      content.push_str(&format!("s{} a > s{}\n", i, (i + 1) % size));
+    }
+    content.push_str("wrd : ");
+    let automaton = Automaton::from_parsed(&file::parse_str(&content));
+
+    let dfa = Dfa::new(&automaton).unwrap();
+    let nfa = Nfa::new(&automaton);
+    let word = "a".repeat(10 * size);
+    assert!(dfa.accepts(Symbols::new(&automaton, &word)));
+    assert!(nfa.accepts(Symbols::new(&automaton, &word)));
+    assert!(!dfa.accepts(Symbols::new(&automaton, &word[1..])));
+    assert!(!nfa.accepts(Symbols::new(&automaton, &word[1..])));
+  }
+
+  #[test]
+  fn agrees_with_set_simulation() {
+    let infos = file::parse("./inputs/afn_exemplo.txt");
+    let automaton = Automaton::from_parsed(&infos);
+    let compiled = Compiled::new(&automaton);
+    for word in &["0011", "0101", "00", "1", "110", "0x11"] {
+      let tokens = file::tokenize(word, &infos.get_alphabet());
+      assert_eq!(
+        compiled.accepts(Symbols::new(&automaton, word)),
+        crate::afdn::afn::accepts(&infos, &tokens),
+        "{}",
+        word
+      );
+    }
+  }
+}
@@ -0,0 +1,438 @@
+use crate::afdn::{afn::LAMBDA, Node, NodeVec};
+use crate::util::file::ParsedFile;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// # Expression
+/// Expressão sobre autômatos nomeados (linhas `@module`): concatenação (`.`), união (`|`) e fecho
+/// de Kleene (`*`), com parênteses. O `*` tem a maior precedência e o `|` a menor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+  Module(String),
+  Concat(Box<Expression>, Box<Expression>),
+  Union(Box<Expression>, Box<Expression>),
+  Star(Box<Expression>),
+}
+
+/// # Error
+/// Erros ao montar o autômato de uma expressão.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  /// Expressão malformada (com a descrição do problema).
+  Syntax(String),
+  UnknownModule(String),
+  /// Dois estados gerados com o mesmo nome, mesmo após o prefixo do módulo.
+  Clash(String),
+}
+impl std::fmt::Display for Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Error::Syntax(message) => write!(f, "{}", message),
+      Error::UnknownModule(name) => write!(
+        f,
+        "o módulo {} não foi declarado. Use @module {} <arquivo>",
+        name, name
+      ),
+      Error::Clash(state) => write!(
+        f,
+        "o estado {} foi gerado mais de uma vez. Renomeie um dos módulos ou estados",
+        state
+      ),
+    }
+  }
+}
+
+/// Divide a expressão em nomes de módulos e operadores.
+fn tokenize(expression: &str) -> Result<Vec<String>, Error> {
+  let mut tokens: Vec<String> = Vec::new();
+  let mut chars = expression.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '.' | '|' | '*' | '(' | ')' => tokens.push(c.to_string()),
+      c if c.is_whitespace() => {}
+      c if c.is_alphanumeric() || c == '_' => {
+        let mut name = c.to_string();
+        while let Some(next) = chars.peek().filter(|e| e.is_alphanumeric() || **e == '_') {
+          name.push(*next);
+          chars.next();
+        }
+        tokens.push(name);
+      }
+      c => return Err(Error::Syntax(format!("caracter inesperado: {}", c))),
+    }
+  }
+  Ok(tokens)
+}
+
+/// Máximo de parênteses aninhados na expressão.
+const MAX_DEPTH: usize = 64;
+
+/// Parser descendente recursivo:
+///
+/// <pre>
+/// união         := concatenação ('|' concatenação)*
+/// concatenação  := fecho ('.' fecho)*
+/// fecho         := átomo '*'*
+/// átomo         := módulo | '(' união ')'
+/// </pre>
+struct Parser {
+  tokens: Vec<String>,
+  position: usize,
+  /// Parênteses abertos no momento (limitados pelo [`MAX_DEPTH`]).
+  depth: usize,
+}
+impl Parser {
+  fn peek(&self) -> Option<&str> {
+    self.tokens.get(self.position).map(|e| e.as_str())
+  }
+  fn eat(&mut self, token: &str) -> bool {
+    let found = self.peek() == Some(token);
+    if found {
+      self.position += 1;
+    }
+    found
+  }
+
+  fn union(&mut self) -> Result<Expression, Error> {
+    let mut left = self.concat()?;
+    while self.eat("|") {
+      left = Expression::Union(Box::new(left), Box::new(self.concat()?));
+    }
+    Ok(left)
+  }
+  fn concat(&mut self) -> Result<Expression, Error> {
+    let mut left = self.star()?;
+    while self.eat(".") {
+      left = Expression::Concat(Box::new(left), Box::new(self.star()?));
+    }
+    Ok(left)
+  }
+  fn star(&mut self) -> Result<Expression, Error> {
+    let mut expression = self.atom()?;
+    while self.eat("*") {
+      expression = Expression::Star(Box::new(expression));
+    }
+    Ok(expression)
+  }
+  fn atom(&mut self) -> Result<Expression, Error> {
+    if self.eat("(") {
+      self.depth += 1;
+      if self.depth > MAX_DEPTH {
+        return Err(Error::Syntax(format!(
+          "mais de {} parênteses aninhados",
+          MAX_DEPTH
+        )));
+      }
+      let expression = self.union()?;
+      if !self.eat(")") {
+        return Err(Error::Syntax("esperado )".to_string()));
+      }
+      self.depth -= 1;
+      return Ok(expression);
+    }
+    match self.peek() {
+      Some(name) if !".|*()".contains(name) => {
+        let name = name.to_string();
+        self.position += 1;
+        Ok(Expression::Module(name))
+      }
+      Some(token) => Err(Error::Syntax(format!("{} inesperado", token))),
+      None => Err(Error::Syntax("expressão incompleta".to_string())),
+    }
+  }
+}
+
+/// Faz o parse da expressão, e.g. `A . B*` ou `(A | B) . C`.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::compose::{parse, Error};
+/// let expression = parse("A . B*")?; // Concat(Module("A"), Star(Module("B")))
+/// # Ok::<(), Error>(())
+/// ```
+pub fn parse(expression: &str) -> Result<Expression, Error> {
+  let mut parser = Parser {
+    tokens: self::tokenize(expression)?,
+    position: 0,
+    depth: 0,
+  };
+  let parsed = parser.union()?;
+  match parser.peek() {
+    None => Ok(parsed),
+    Some(token) => Err(Error::Syntax(format!("{} inesperado", token))),
+  }
+}
+
+/// Parte do autômato gerada por uma subexpressão (os estados ficam no [`Builder`]).
+struct Fragment {
+  initial: Vec<String>,
+  finals: Vec<String>,
+}
+
+/// Acumula os estados e transições dos fragmentos, garantindo nomes únicos.
+struct Builder<'m> {
+  modules: &'m HashMap<String, ParsedFile>,
+  states: HashMap<String, NodeVec>,
+  names: HashSet<String>,
+  /// Quantas vezes cada módulo já foi usado (para o prefixo das próximas cópias).
+  uses: HashMap<String, usize>,
+  stars: usize,
+  /// Atributos (`@state`) dos módulos, já com os nomes prefixados.
+  attributes: Vec<(String, String, String)>,
+  /// Atributos (`@edge`) dos módulos, com a origem e o destino já prefixados.
+  edge_attributes: Vec<((String, String, String), String, String)>,
+}
+impl<'m> Builder<'m> {
+  fn add_state(&mut self, name: String) -> Result<String, Error> {
+    if !self.names.insert(name.clone()) {
+      return Err(Error::Clash(name));
+    }
+    Ok(name)
+  }
+
+  fn lambda(&mut self, from: &[String], to: &[String]) {
+    for origin in from {
+      let nodes = self.states.entry(origin.to_string()).or_default();
+      for destination in to {
+        nodes.push(Node {
+          character: LAMBDA.to_string(),
+          destination: destination.to_string(),
+        });
+      }
+    }
+  }
+
+  fn build(&mut self, expression: &Expression) -> Result<Fragment, Error> {
+    match expression {
+      Expression::Module(name) => self.module(name),
+      Expression::Concat(left, right) => {
+        let left = self.build(left)?;
+        let right = self.build(right)?;
+        self.lambda(&left.finals, &right.initial);
+        Ok(Fragment {
+          initial: left.initial,
+          finals: right.finals,
+        })
+      }
+      Expression::Union(left, right) => {
+        // o autômato pode possuir vários estados iniciais: basta juntar os dois lados
+        let mut left = self.build(left)?;
+        let right = self.build(right)?;
+        left.initial.extend(right.initial);
+        left.finals.extend(right.finals);
+        Ok(left)
+      }
+      Expression::Star(inner) => {
+        // novo estado inicial e final, que leva aos iniciais e recebe os finais do fragmento
+        let inner = self.build(inner)?;
+        self.stars += 1;
+        let start = self.add_state(format!("fecho{}", self.stars))?;
+        let start = vec![start];
+        self.lambda(&start, &inner.initial);
+        self.lambda(&inner.finals, &start);
+        Ok(Fragment {
+          initial: start.clone(),
+          finals: start,
+        })
+      }
+    }
+  }
+
+  /// Copia o autômato do módulo, prefixando os estados com o nome do módulo (`A_q0`) ou, a partir
+  /// da segunda cópia, com o nome e o número da cópia (`A2_q0`).
+  fn module(&mut self, name: &str) -> Result<Fragment, Error> {
+    let module = self
+      .modules
+      .get(name)
+      .ok_or_else(|| Error::UnknownModule(name.to_string()))?;
+    let uses = self.uses.entry(name.to_string()).or_default();
+    *uses += 1;
+    let prefix = match *uses {
+      1 => format!("{}_", name),
+      n => format!("{}{}_", name, n),
+    };
+    let rename = |state: &String| format!("{}{}", prefix, state);
+
+    for state in module.get_all_states() {
+      self.add_state(rename(&state))?;
+    }
+    for (origin, nodes) in module.get_states() {
+      let renamed: NodeVec = nodes
+        .iter()
+        .map(|e| Node {
+          character: e.character.to_string(),
+          destination: rename(&e.destination),
+        })
+        .collect();
+      self
+        .states
+        .entry(rename(origin))
+        .or_default()
+        .extend(renamed);
+    }
+    for (state, attributes) in module.get_state_attributes() {
+      for (key, value) in attributes {
+        self
+          .attributes
+          .push((rename(state), key.to_string(), value.to_string()));
+      }
+    }
+    for ((origin, symbol, destination), attributes) in module.get_edge_attributes() {
+      for (key, value) in attributes {
+        self.edge_attributes.push((
+          (rename(origin), symbol.to_string(), rename(destination)),
+          key.to_string(),
+          value.to_string(),
+        ));
+      }
+    }
+
+    Ok(Fragment {
+      initial: module.get_initial_states().iter().map(rename).collect(),
+      finals: module.get_finish_state().iter().map(rename).collect(),
+    })
+  }
+}
+
+/// Monta um único autômato (com transições lambda) a partir da expressão sobre os módulos, como na
+/// construção de Thompson. Os estados de cada cópia de um módulo são prefixados com o seu nome e os
+/// estados criados pelo `*` se chamam `fecho1`, `fecho2`, ...
+///
+/// O alfabeto declarado é a união dos alfabetos dos módulos usados, caso algum deles o declare.
+///
+/// ## Arguments
+///
+/// * `expression` - Expressão, e.g. `A . B*`.
+/// * `modules` - Autômatos nomeados.
+/// * `word` - Palavra do autômato gerado.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::compose::{compose, Error};
+/// # use afdn_animator::parsers::parse_str;
+/// # use std::collections::HashMap;
+/// # let mut modules = HashMap::new();
+/// # modules.insert("A".to_string(), parse_str("p0 ; p1\np0 a > p1\nwrd : "));
+/// # modules.insert("B".to_string(), parse_str("p0 ; p1\np0 b > p1\nwrd : "));
+/// let infos = compose("(A | B)*", &modules, "ab")?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn compose(
+  expression: &str,
+  modules: &HashMap<String, ParsedFile>,
+  word: &str,
+) -> Result<ParsedFile, Error> {
+  let expression = self::parse(expression)?;
+  let mut builder = Builder {
+    modules,
+    states: HashMap::new(),
+    names: HashSet::new(),
+    uses: HashMap::new(),
+    stars: 0,
+    attributes: Vec::new(),
+    edge_attributes: Vec::new(),
+  };
+  let fragment = builder.build(&expression)?;
+  debug!("Composed {:?}: {:#?}", &expression, &builder.states);
+
+  let mut infos = ParsedFile::new(
+    fragment.initial,
+    fragment.finals,
+    builder.states,
+    word.to_string(),
+  );
+  let used: Vec<&ParsedFile> = builder.uses.keys().map(|name| &modules[name]).collect();
+  if used.iter().any(|e| e.get_declared_alphabet().is_some()) {
+    let alphabet: BTreeSet<String> = used.iter().flat_map(|e| e.get_alphabet()).collect();
+    infos.set_alphabet(Some(alphabet));
+  }
+  for (state, key, value) in builder.attributes {
+    infos.set_state_attribute(&state, &key, &value);
+  }
+  for ((origin, symbol, destination), key, value) in builder.edge_attributes {
+    infos.set_edge_attribute((&origin, &symbol, &destination), &key, &value);
+  }
+  Ok(infos)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Error, Expression};
+  use crate::language;
+  use crate::util::file;
+  use std::collections::HashMap;
+
+  #[test]
+  fn parses_with_precedence() {
+    let module = |name: &str| Box::new(Expression::Module(name.to_string()));
+    assert_eq!(
+      super::parse("A . B* | C").unwrap(),
+      Expression::Union(
+        Box::new(Expression::Concat(
+          module("A"),
+          Box::new(Expression::Star(module("B")))
+        )),
+        module("C")
+      )
+    );
+    assert_eq!(
+      super::parse("(A | B"),
+      Err(Error::Syntax("esperado )".to_string()))
+    );
+    assert!(super::parse("A . | B").is_err());
+    assert!(super::parse("A B").is_err());
+  }
+
+  #[test]
+  fn composes_named_automata() {
+    let mut modules = HashMap::new();
+    // A: somente "a"; B: somente "b"
+    modules.insert(
+      "A".to_string(),
+      file::parse_str("q0 ; q1\nq0 a > q1\nwrd : "),
+    );
+    modules.insert(
+      "B".to_string(),
+      file::parse_str("q0 ; q1\nq0 b > q1\nwrd : "),
+    );
+
+    // a(b)* concatenado com a: a b* a
+    let infos = super::compose("A . B* . A", &modules, "abba").unwrap();
+    assert!(infos.get_all_states().contains("A2_q0"));
+    let expected = file::parse_str("s0 ; s2\ns0 a > s1\ns1 b > s1\ns1 a > s2\nwrd : ");
+    assert_eq!(language::counterexample(&infos, &expected), None);
+
+    assert_eq!(
+      super::compose("A . C", &modules, "").err(),
+      Some(Error::UnknownModule("C".to_string()))
+    );
+
+    // A_q0_x (estado do módulo A) e A_q0 com o estado x (módulo A_q0) geram o mesmo nome
+    modules.insert(
+      "A".to_string(),
+      file::parse_str("q0_x ; q1\nq0_x a > q1\nwrd : "),
+    );
+    modules.insert("A_q0".to_string(), file::parse_str("x ; x\nwrd : "));
+    assert_eq!(
+      super::compose("A | A_q0", &modules, "").err(),
+      Some(Error::Clash("A_q0_x".to_string()))
+    );
+  }
+
+  #[test]
+  fn keeps_module_attributes() {
+    let mut modules = HashMap::new();
+    modules.insert(
+      "A".to_string(),
+      file::parse_str("q0 ; q1\nq0 a > q1\n@state q1 color=red\n@edge q0 a q1 color=blue\nwrd : "),
+    );
+
+    let infos = super::compose("A . A", &modules, "").unwrap();
+    for (origin, destination) in [("A_q0", "A_q1"), ("A2_q0", "A2_q1")] {
+      assert_eq!(infos.get_state_attributes()[destination]["color"], "red");
+      let edge = (origin.to_string(), "a".to_string(), destination.to_string());
+      assert_eq!(infos.get_edge_attributes()[&edge]["color"], "blue");
+    }
+  }
+}
@@ -0,0 +1,1009 @@
+pub mod lambda {
+  use crate::afdn::{afn::LAMBDA, Node, NodeVec};
+  use crate::dot;
+  use crate::util::file::ParsedFile;
+  use std::collections::{BTreeSet, HashMap};
+
+  /// Calcula o fecho-lambda de um conjunto de estados, i.e, todos os estados alcançáveis
+  /// somente através de transições lambda (incluindo os próprios estados).
+  ///
+  /// ## Arguments
+  ///
+  /// * `states` - O grafo (hashmap) do autômato.
+  /// * `from` - Estados de onde partir.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::lambda::closure;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q2\nq0 / > q1\nq1 a > q2\nwrd : a");
+  /// let fecho = closure(infos.get_states(), vec!["q0".to_string()]); // {q0, q1}
+  /// ```
+  pub fn closure<I>(states: &HashMap<String, NodeVec>, from: I) -> BTreeSet<String>
+  where
+    I: IntoIterator<Item = String>,
+  {
+    let mut reached: BTreeSet<String> = BTreeSet::new();
+    let mut stack: Vec<String> = from.into_iter().collect();
+
+    while let Some(state) = stack.pop() {
+      if !reached.insert(state.clone()) {
+        // já visitado
+        continue;
+      }
+      if let Some(nodes) = states.get(&state) {
+        for node in nodes.iter().filter(|e| e.character == LAMBDA) {
+          stack.push(node.destination.clone());
+        }
+      }
+    }
+
+    reached
+  }
+
+  /// Remove as transições lambda de um AFN, gerando um AFN equivalente sem `/`.
+  ///
+  /// Para cada estado `q`, as novas transições são `δ'(q, a) = δ(fecho(q), a)` e `q` passa a ser
+  /// final caso o seu fecho contenha algum estado final.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::lambda::remove;
+  /// # use afdn_animator::parsers::ParsedFile;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q2\nq0 / > q1\nq1 a > q2\nwrd : a");
+  /// let sem_lambda: ParsedFile = remove(&infos);
+  /// ```
+  pub fn remove(infos: &ParsedFile) -> ParsedFile {
+    debug!("Removing lambda transitions...");
+
+    let mut states: HashMap<String, NodeVec> = HashMap::new();
+    let mut finish_states: Vec<String> = Vec::new();
+    for state in &infos.get_all_states() {
+      let reached = self::closure(infos.get_states(), vec![state.to_string()]);
+      debug!("\t - Closure of {}: {:?}", state, &reached);
+
+      // copia as transições (não lambda) de todos os estados do fecho
+      let mut nodes: NodeVec = Vec::new();
+      for p in &reached {
+        if let Some(edges) = infos.get_states().get(p) {
+          for edge in edges.iter().filter(|e| e.character != LAMBDA) {
+            if !nodes.contains(edge) {
+              nodes.push(edge.clone());
+            }
+          }
+        }
+      }
+      if !nodes.is_empty() {
+        states.insert(state.to_string(), nodes);
+      }
+
+      if reached.iter().any(|e| infos.get_finish_state().contains(e)) {
+        finish_states.push(state.to_string());
+      }
+    }
+
+    let mut lambda_free = ParsedFile::new(
+      infos.get_initial_states().to_vec(),
+      finish_states,
+      states,
+      infos.get_word().to_string(),
+    );
+    lambda_free.set_alphabet(infos.get_declared_alphabet().cloned());
+    lambda_free.copy_attributes(infos);
+    lambda_free
+  }
+
+  /// Exporta um grafo colorindo as arestas em `highlight` (e os nós em `highlight_nodes`).
+  fn save_dot_file(
+    infos: &ParsedFile,
+    highlight: &[(String, Node)],
+    edge_style: &str,
+    highlight_nodes: &[String],
+    filename: &str,
+  ) {
+    debug!("#Graph Saving graph in file.");
+    let mut output = dot::header(infos);
+
+    // destaca os nós
+    for node in highlight_nodes {
+      output.push_str(&format!(
+        "\n\t{} [color=\"#467050\", fontcolor=\"white\"]",
+        dot::dot_id(node)
+      ));
+    }
+    output.push('\n');
+
+    let mut keys: Vec<&String> = infos.get_states().keys().collect();
+    keys.sort();
+    for origin in keys {
+      for node in infos.get_states().get(origin).unwrap() {
+        let mut additional_configs: String =
+          format!("[{}", dot::edge_attributes(infos, origin, node));
+        if highlight.contains(&(origin.to_string(), node.clone())) {
+          additional_configs = format!("{}, {}", &additional_configs, edge_style);
+        }
+        additional_configs = format!("{}]", &additional_configs);
+
+        output.push_str(&format!(
+          "\t{} -> {} {}\n",
+          dot::dot_id(origin),
+          dot::dot_id(&node.destination),
+          &additional_configs
+        ));
+      }
+    }
+
+    dot::save(output, filename);
+  }
+
+  /// Gera os grafos de antes e depois da remoção das transições lambda:
+  /// - `lambda_antes.dot`: autômato original, com as arestas `/` (que irão sumir) em vermelho;
+  /// - `lambda_depois.dot`: autômato sem lambda, com as arestas adicionadas e os novos estados
+  ///   finais em verde.
+  ///
+  /// ## Arguments
+  ///
+  /// * `before` - O autômato original.
+  /// * `after` - O autômato gerado pelo [`remove`].
+  /// * `dir` - Diretório onde salvar os arquivos.
+  pub fn save_dot_files(before: &ParsedFile, after: &ParsedFile, dir: &str) {
+    let mut removed: Vec<(String, Node)> = Vec::new();
+    for (origin, nodes) in before.get_states() {
+      for node in nodes.iter().filter(|e| e.character == LAMBDA) {
+        removed.push((origin.to_string(), node.clone()));
+      }
+    }
+
+    let mut added: Vec<(String, Node)> = Vec::new();
+    for (origin, nodes) in after.get_states() {
+      for node in nodes {
+        let existed = match before.get_states().get(origin) {
+          Some(old) => old.contains(node),
+          None => false,
+        };
+        if !existed {
+          added.push((origin.to_string(), node.clone()));
+        }
+      }
+    }
+    debug!("Removed edges: {:?}\nAdded edges: {:?}", &removed, &added);
+
+    let new_finals: Vec<String> = after
+      .get_finish_state()
+      .iter()
+      .filter(|e| !before.get_finish_state().contains(e))
+      .cloned()
+      .collect();
+
+    self::save_dot_file(
+      before,
+      &removed,
+      "color=\"#ad2a2a\", style=\"dashed\"",
+      &[],
+      &format!("{}/lambda_antes.dot", dir),
+    );
+    self::save_dot_file(
+      after,
+      &added,
+      "color=\"#467050\", penwidth=2",
+      &new_finals,
+      &format!("{}/lambda_depois.dot", dir),
+    );
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    fn node(character: &str, destination: &str) -> Node {
+      Node {
+        character: character.to_string(),
+        destination: destination.to_string(),
+      }
+    }
+
+    #[test]
+    fn removes_lambda_edges() {
+      // q1 -/-> q2 -a-> q3 (final) ; q2 -/-> q3
+      let mut states = HashMap::new();
+      states.insert("q1".to_string(), vec![node("/", "q2")]);
+      states.insert("q2".to_string(), vec![node("a", "q3"), node("/", "q3")]);
+      let infos = ParsedFile::new(
+        vec!["q1".to_string()],
+        vec!["q3".to_string()],
+        states,
+        "a".to_string(),
+      );
+      assert!(!infos.is_afd());
+
+      let after = super::remove(&infos);
+      assert!(after
+        .get_states()
+        .values()
+        .all(|nodes| nodes.iter().all(|e| e.character != "/")));
+      assert_eq!(
+        after.get_states().get("q1").unwrap(),
+        &vec![node("a", "q3")]
+      );
+
+      let mut finals = after.get_finish_state().clone();
+      finals.sort();
+      assert_eq!(finals, vec!["q1", "q2", "q3"]);
+    }
+  }
+}
+
+pub mod complete {
+  use crate::afdn::{Node, NodeVec};
+  use crate::util::file::ParsedFile;
+  use std::collections::HashMap;
+
+  /// Nome padrão do estado armadilha (morto).
+  pub const TRAP: &str = "armadilha";
+
+  /// Completa um AFD parcial, i.e, adiciona um estado armadilha (com laços para todos os símbolos)
+  /// e uma transição para ele em todo par `(estado, símbolo)` que não possua transição.
+  /// O alfabeto usado é o declarado na linha `alf :` ou, caso não exista, o inferido das transições.
+  ///
+  /// Caso o autômato já seja total (ou o alfabeto seja vazio), é retornada uma cópia sem o estado
+  /// armadilha. Somente AFDs podem ser completados: em um AFN, um par sem transição não significa
+  /// rejeição (há outros ramos e as lambdas), então a entrada deve ser determinizada antes.
+  ///
+  /// ## Panics
+  ///
+  /// Caso o autômato não seja um AFD.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  /// * `trap` - Nome do estado armadilha. Caso já exista no autômato, recebe um sufixo numérico
+  ///   (`armadilha_2`, `armadilha_3`, ...).
+  /// * `hide_trap` - Se verdadeiro, o estado armadilha não será desenhado nos arquivos dot.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::complete::{complete, TRAP};
+  /// # use afdn_animator::parsers::ParsedFile;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+  /// let total: ParsedFile = complete(&infos, TRAP, false);
+  /// ```
+  pub fn complete(infos: &ParsedFile, trap: &str, hide_trap: bool) -> ParsedFile {
+    if !infos.is_afd() {
+      panic!("Somente AFDs podem ser completados (determinize o AFN antes)");
+    }
+    let alphabet = infos.get_alphabet();
+    if alphabet.is_empty() {
+      // sem símbolos, o autômato já é total
+      return infos.clone();
+    }
+
+    let all_states = infos.get_all_states();
+    let base = trap;
+    let mut trap = base.to_string();
+    let mut suffix = 2;
+    while all_states.contains(&trap) {
+      trap = format!("{}_{}", base, suffix);
+      suffix += 1;
+    }
+    debug!("Completing automaton with trap state {}", trap);
+
+    let mut states: HashMap<String, NodeVec> = infos.get_states().clone();
+    let mut used_trap = false;
+    for state in &all_states {
+      let nodes = states.entry(state.to_string()).or_default();
+      for symbol in &alphabet {
+        if !nodes.iter().any(|e| &e.character == symbol) {
+          debug!("\t - Missing ({}, {}). Going to trap", state, symbol);
+          used_trap = true;
+          nodes.push(Node {
+            character: symbol.to_string(),
+            destination: trap.to_string(),
+          });
+        }
+      }
+    }
+
+    if used_trap {
+      // laços do estado armadilha
+      let loops: NodeVec = alphabet
+        .iter()
+        .map(|symbol| Node {
+          character: symbol.to_string(),
+          destination: trap.to_string(),
+        })
+        .collect();
+      states.insert(trap.to_string(), loops);
+    }
+
+    let mut total = ParsedFile::new(
+      infos.get_initial_states().to_vec(),
+      infos.get_finish_state().to_vec(),
+      states,
+      infos.get_word().to_string(),
+    );
+    total.set_alphabet(infos.get_declared_alphabet().cloned());
+    total.copy_attributes(infos);
+    if used_trap && hide_trap {
+      total.set_hidden_states(vec![trap]);
+    }
+    total
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use crate::util::file::{parse_str, ParsedFile};
+    use std::collections::HashMap;
+
+    #[test]
+    fn adds_trap_state_for_missing_pairs() {
+      // s0 -a-> s1 (final), s1 sem transições
+      let mut states = HashMap::new();
+      states.insert(
+        "s0".to_string(),
+        vec![Node {
+          character: "a".to_string(),
+          destination: "s1".to_string(),
+        }],
+      );
+      states.insert(
+        "s1".to_string(),
+        vec![Node {
+          character: "b".to_string(),
+          destination: "s0".to_string(),
+        }],
+      );
+      let infos = ParsedFile::new(
+        vec!["s0".to_string()],
+        vec!["s1".to_string()],
+        states,
+        "ab".to_string(),
+      );
+
+      let total = super::complete(&infos, super::TRAP, true);
+      assert!(total.is_afd());
+      assert_eq!(total.get_hidden_states(), &vec![super::TRAP.to_string()]);
+      for state in &["s0", "s1", super::TRAP] {
+        let symbols: Vec<&String> = total
+          .get_states()
+          .get(*state)
+          .unwrap()
+          .iter()
+          .map(|e| &e.character)
+          .collect();
+        assert_eq!(symbols.len(), 2, "State {} is not total", state);
+      }
+    }
+
+    #[test]
+    fn renames_trap_state_that_already_exists() {
+      let infos = parse_str("armadilha ; armadilha_2\narmadilha a > armadilha_2\nwrd : a");
+
+      let total = super::complete(&infos, super::TRAP, true);
+      assert!(total.is_afd());
+      assert_eq!(total.get_hidden_states(), &vec!["armadilha_3".to_string()]);
+      assert_eq!(total.get_states()["armadilha"].len(), 1);
+      assert_eq!(
+        total.get_states()["armadilha_2"][0].destination,
+        "armadilha_3"
+      );
+    }
+
+    #[test]
+    fn keeps_automaton_without_symbols() {
+      let infos = parse_str("q0 ; q0\nwrd : ");
+      let total = super::complete(&infos, super::TRAP, false);
+      assert_eq!(total.get_all_states().len(), 1);
+      assert!(total.get_states().values().all(|e| !e.is_empty()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Somente AFDs")]
+    fn rejects_nondeterministic_automata() {
+      let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+      super::complete(&infos, super::TRAP, false);
+    }
+  }
+}
+
+pub mod determinize {
+  use crate::afdn::{Node, NodeVec};
+  use crate::automaton::{Automaton, StateId};
+  use crate::util::file::ParsedFile;
+  use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+  /// Nome do estado do AFD que representa um conjunto de estados do AFN (e.g. `q0_q1`).
+  ///
+  /// O nome não é único quando os estados possuem `_` (`{a, b}` e `{a_b}` geram `a_b`), por isso a
+  /// determinização usa o [`Names`], que desambigua os nomes repetidos.
+  pub fn subset_name(subset: &BTreeSet<String>) -> String {
+    subset.iter().cloned().collect::<Vec<String>>().join("_")
+  }
+
+  /// Nomes dados aos subconjuntos: o [`subset_name`] ou, caso ele já pertença a outro subconjunto,
+  /// o mesmo nome seguido de um sufixo numérico (`a_b_2`).
+  struct Names<'a> {
+    automaton: &'a Automaton,
+    names: HashMap<BTreeSet<StateId>, String>,
+    used: HashSet<String>,
+  }
+  impl<'a> Names<'a> {
+    fn get(&mut self, subset: &BTreeSet<StateId>) -> String {
+      if let Some(name) = self.names.get(subset) {
+        return name.to_string();
+      }
+      let states: BTreeSet<String> = subset
+        .iter()
+        .map(|e| self.automaton.state_name(*e).to_string())
+        .collect();
+      let base = self::subset_name(&states);
+      let mut name = base.to_string();
+      let mut suffix = 2;
+      while self.used.contains(&name) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+      }
+      self.used.insert(name.to_string());
+      self.names.insert(subset.clone(), name.to_string());
+      name
+    }
+  }
+
+  /// Converte um AFN em um AFD equivalente (construção dos subconjuntos).
+  ///
+  /// O estado inicial do AFD é o fecho lambda de **todos** os estados iniciais do AFN, tratados
+  /// como um único estado combinado. Somente os subconjuntos alcançáveis são gerados e o conjunto
+  /// vazio não é criado, i.e, o AFD resultante pode ser parcial (veja o `complete`).
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::determinize::determinize;
+  /// # use afdn_animator::parsers::ParsedFile;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let afd: ParsedFile = determinize(&infos);
+  /// ```
+  pub fn determinize(infos: &ParsedFile) -> ParsedFile {
+    debug!("Determinizing automaton...");
+    let automaton = Automaton::from_parsed(infos);
+
+    let start = automaton.closure(automaton.initial_states().clone());
+    let mut visited: BTreeSet<BTreeSet<StateId>> = BTreeSet::new();
+    let mut queue: VecDeque<BTreeSet<StateId>> = VecDeque::new();
+    visited.insert(start.clone());
+    queue.push_back(start.clone());
+
+    let mut names = Names {
+      automaton: &automaton,
+      names: HashMap::new(),
+      used: HashSet::new(),
+    };
+    let mut states: HashMap<String, NodeVec> = HashMap::new();
+    let mut finish_states: Vec<String> = Vec::new();
+    while let Some(subset) = queue.pop_front() {
+      let name = names.get(&subset);
+      debug!("\t - Subset {}", &name);
+      if subset.iter().any(|e| automaton.is_final(*e)) {
+        finish_states.push(name.to_string());
+      }
+
+      let mut nodes: NodeVec = Vec::new();
+      for (id, symbol) in automaton.alphabet().iter().enumerate() {
+        let next = automaton.step(&subset, id);
+        if next.is_empty() {
+          continue;
+        }
+        nodes.push(Node {
+          character: symbol.to_string(),
+          destination: names.get(&next),
+        });
+        if visited.insert(next.clone()) {
+          queue.push_back(next);
+        }
+      }
+      if !nodes.is_empty() {
+        states.insert(name, nodes);
+      }
+    }
+
+    let mut afd = ParsedFile::new(
+      vec![names.get(&start)],
+      finish_states,
+      states,
+      infos.get_word().to_string(),
+    );
+    afd.set_alphabet(infos.get_declared_alphabet().cloned());
+    afd
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    #[test]
+    fn combines_initial_states_and_closures() {
+      let node = |character: &str, destination: &str| Node {
+        character: character.to_string(),
+        destination: destination.to_string(),
+      };
+      // p0 e q0 iniciais; p0 -a-> p1, q0 -/-> q1, q1 -a-> q1, q1 -b-> p1
+      let mut states = HashMap::new();
+      states.insert("p0".to_string(), vec![node("a", "p1")]);
+      states.insert("q0".to_string(), vec![node("/", "q1")]);
+      states.insert("q1".to_string(), vec![node("a", "q1"), node("b", "p1")]);
+      let infos = ParsedFile::new(
+        vec!["p0".to_string(), "q0".to_string()],
+        vec!["p1".to_string()],
+        states,
+        String::new(),
+      );
+
+      let afd = super::determinize(&infos);
+      assert!(afd.is_afd());
+      assert_eq!(afd.get_initial_states(), &vec!["p0_q0_q1".to_string()]);
+      assert_eq!(
+        afd.get_states().get("p0_q0_q1").unwrap(),
+        &vec![node("a", "p1_q1"), node("b", "p1")]
+      );
+      let mut finals = afd.get_finish_state().clone();
+      finals.sort();
+      assert_eq!(finals, vec!["p1", "p1_q1"]);
+    }
+
+    #[test]
+    fn keeps_symbols_missing_from_the_alphabet() {
+      // `b` é usado nas transições, mas não foi declarado
+      let infos = crate::util::file::parse_str("q0 ; q1\nalf : a\nq0 a > q0\nq0 b > q1\nwrd : ");
+      let afd = super::determinize(&infos);
+      assert_eq!(
+        crate::language::counterexample(&infos, &afd),
+        None,
+        "{}",
+        crate::util::file::serialize(&afd)
+      );
+    }
+
+    #[test]
+    fn names_subsets_injectively() {
+      // `{a, b}` e `{a_b}` não podem ser o mesmo estado
+      let infos = crate::util::file::parse_str("a ; a_b\na x > a\na x > b\na y > a_b\nwrd : x");
+      let afd = super::determinize(&infos);
+      assert_eq!(afd.get_all_states().len(), 3);
+      assert!(!crate::afdn::afd::accepts(&afd, &["x".to_string()]));
+      assert_eq!(crate::language::counterexample(&infos, &afd), None);
+      assert_eq!(
+        crate::language::shortest_accepted(&infos),
+        Some(vec!["y".to_string()])
+      );
+    }
+  }
+}
+
+pub mod minimize {
+  use crate::afdn::{Node, NodeVec};
+  use crate::automaton::{Automaton, StateId, SymbolId};
+  use crate::convert::determinize::determinize;
+  use crate::util::file::ParsedFile;
+  use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+  /// Gera o AFD mínimo (refinamento de partições de Moore) equivalente ao autômato.
+  ///
+  /// O autômato é determinizado e os estados que não alcançam nenhum estado final são removidos
+  /// antes do refinamento, i.e, o resultado é parcial (sem estado armadilha, veja o `complete`).
+  /// Cada classe recebe o nome do menor estado que ela contém.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - O arquivo de entrada lido e mapeado.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::minimize::minimize;
+  /// # use afdn_animator::parsers::ParsedFile;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let minimal: ParsedFile = minimize(&infos);
+  /// ```
+  pub fn minimize(infos: &ParsedFile) -> ParsedFile {
+    debug!("Minimizing automaton...");
+    let afd = Automaton::from_parsed(&determinize(infos));
+    let start: StateId = *afd.initial_states().iter().next().unwrap();
+
+    // estados úteis (o inicial é mantido mesmo que a linguagem seja vazia); os ids seguem a ordem
+    // dos nomes
+    let useful = afd.co_reachable();
+    let states: Vec<StateId> = (0..afd.states().len())
+      .filter(|e| useful.contains(e) || *e == start)
+      .collect();
+    let symbols: Vec<SymbolId> = (0..afd.alphabet().len()).collect();
+    let delta = |state: StateId, symbol: SymbolId| -> Option<StateId> {
+      afd.next(state, symbol).filter(|e| useful.contains(e))
+    };
+
+    // partição inicial: finais e não finais
+    let mut class: HashMap<StateId, usize> = states
+      .iter()
+      .map(|e| (*e, afd.is_final(*e) as usize))
+      .collect();
+    let mut count = 0;
+    loop {
+      // assinatura: classe atual e classe de cada destino (transição ausente = armadilha)
+      let mut signatures: BTreeMap<(usize, Vec<Option<usize>>), usize> = BTreeMap::new();
+      let mut refined: HashMap<StateId, usize> = HashMap::new();
+      for state in &states {
+        let targets: Vec<Option<usize>> = symbols
+          .iter()
+          .map(|symbol| delta(*state, *symbol).map(|e| class[&e]))
+          .collect();
+        let next = signatures.len();
+        let id = *signatures.entry((class[state], targets)).or_insert(next);
+        refined.insert(*state, id);
+      }
+      class = refined;
+      if signatures.len() == count {
+        break;
+      }
+      count = signatures.len();
+    }
+    debug!("\t - {} classes", count);
+
+    // nome de cada classe: o menor estado (os estados já estão ordenados)
+    let mut names: BTreeMap<usize, &str> = BTreeMap::new();
+    for state in &states {
+      names.entry(class[state]).or_insert(afd.state_name(*state));
+    }
+    let name = |state: StateId| names[&class[&state]].to_string();
+
+    let mut transitions: HashMap<String, NodeVec> = HashMap::new();
+    let mut finish_states: BTreeSet<String> = BTreeSet::new();
+    for state in &states {
+      if afd.is_final(*state) {
+        finish_states.insert(name(*state));
+      }
+      if transitions.contains_key(&name(*state)) {
+        continue;
+      }
+      let nodes: NodeVec = symbols
+        .iter()
+        .filter_map(|symbol| {
+          delta(*state, *symbol).map(|destination| Node {
+            character: afd.alphabet()[*symbol].to_string(),
+            destination: name(destination),
+          })
+        })
+        .collect();
+      if !nodes.is_empty() {
+        transitions.insert(name(*state), nodes);
+      }
+    }
+
+    let mut minimal = ParsedFile::new(
+      vec![name(start)],
+      finish_states.into_iter().collect(),
+      transitions,
+      infos.get_word().to_string(),
+    );
+    minimal.set_alphabet(infos.get_declared_alphabet().cloned());
+    minimal
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use crate::util::file::ParsedFile;
+    use std::collections::HashMap;
+
+    #[test]
+    fn merges_equivalent_states() {
+      // terminadas em "b": q1 e q2 são equivalentes e q3 é inútil
+      let mut states = HashMap::new();
+      let node = |character: &str, destination: &str| Node {
+        character: character.to_string(),
+        destination: destination.to_string(),
+      };
+      states.insert("q0".to_string(), vec![node("a", "q0"), node("b", "q1")]);
+      states.insert("q1".to_string(), vec![node("a", "q0"), node("b", "q2")]);
+      states.insert(
+        "q2".to_string(),
+        vec![node("a", "q0"), node("b", "q2"), node("c", "q3")],
+      );
+      states.insert("q3".to_string(), vec![node("c", "q3")]);
+      let infos = ParsedFile::new(
+        vec!["q0".to_string()],
+        vec!["q1".to_string(), "q2".to_string()],
+        states,
+        String::new(),
+      );
+
+      let minimal = super::minimize(&infos);
+      assert_eq!(minimal.get_all_states().len(), 2);
+      assert_eq!(minimal.get_finish_state(), &vec!["q1".to_string()]);
+      assert_eq!(minimal.get_states()["q1"].len(), 2);
+    }
+
+    #[test]
+    fn keeps_symbols_missing_from_the_alphabet() {
+      // `b` é usado nas transições, mas não foi declarado
+      let infos = crate::util::file::parse_str("q0 ; q1\nalf : a\nq0 a > q0\nq0 b > q1\nwrd : ");
+      let minimal = super::minimize(&infos);
+      assert_eq!(crate::language::counterexample(&infos, &minimal), None);
+
+      let without_b = crate::util::file::parse_str("q0 ; q1\nalf : a\nq0 a > q0\nwrd : ");
+      assert_eq!(
+        crate::language::counterexample(&infos, &without_b),
+        Some(vec!["b".to_string()])
+      );
+    }
+  }
+}
+
+pub mod canonical {
+  use crate::afdn::{Node, NodeVec};
+  use crate::convert::determinize::determinize;
+  use crate::util::file::ParsedFile;
+  use std::collections::{BTreeSet, HashMap, VecDeque};
+
+  /// Destino da transição `(state, symbol)` de um AFD, caso exista.
+  fn next<'a>(infos: &'a ParsedFile, state: &str, symbol: &str) -> Option<&'a String> {
+    infos
+      .get_states()
+      .get(state)
+      .and_then(|nodes| nodes.iter().find(|e| e.character == symbol))
+      .map(|e| &e.destination)
+  }
+
+  /// Renomeia os estados do AFD para `q0`, `q1`, ... na ordem da busca em largura a partir do
+  /// estado inicial, percorrendo o alfabeto em ordem. Assim, dois AFDs isomorfos possuem a mesma
+  /// forma canônica. Estados inalcançáveis são descartados e AFNs são determinizados antes.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::{canonical::canonicalize, minimize::minimize};
+  /// # use afdn_animator::util::file;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// println!("{}", file::serialize(&canonicalize(&minimize(&infos))));
+  /// ```
+  pub fn canonicalize(infos: &ParsedFile) -> ParsedFile {
+    let afd = if infos.is_afd() {
+      infos.clone()
+    } else {
+      determinize(infos)
+    };
+    let alphabet = infos.get_full_alphabet();
+
+    let start = &afd.get_initial_states()[0];
+    let mut names: HashMap<&String, String> = HashMap::new();
+    let mut queue: VecDeque<&String> = VecDeque::new();
+    names.insert(start, "q0".to_string());
+    queue.push_back(start);
+
+    let mut states: HashMap<String, NodeVec> = HashMap::new();
+    while let Some(state) = queue.pop_front() {
+      let mut nodes: NodeVec = Vec::new();
+      for symbol in &alphabet {
+        if let Some(destination) = self::next(&afd, state, symbol) {
+          if !names.contains_key(destination) {
+            names.insert(destination, format!("q{}", names.len()));
+            queue.push_back(destination);
+          }
+          nodes.push(Node {
+            character: symbol.to_string(),
+            destination: names[destination].to_string(),
+          });
+        }
+      }
+      if !nodes.is_empty() {
+        states.insert(names[state].to_string(), nodes);
+      }
+    }
+
+    let mut finish_states: Vec<String> = afd
+      .get_finish_state()
+      .iter()
+      .filter_map(|e| names.get(e).cloned())
+      .collect();
+    finish_states.sort_by_key(|e| e[1..].parse::<usize>().unwrap());
+
+    let mut canonical = ParsedFile::new(
+      vec!["q0".to_string()],
+      finish_states,
+      states,
+      infos.get_word().to_string(),
+    );
+    canonical.set_alphabet(infos.get_declared_alphabet().cloned());
+    canonical
+  }
+
+  /// Verifica se os dois AFDs são isomorfos (considerando somente os estados alcançáveis),
+  /// percorrendo ambos simultaneamente a partir dos estados iniciais.
+  ///
+  /// Retorna o mapeamento entre os estados (na ordem da busca) ou a descrição da primeira
+  /// diferença estrutural encontrada.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::convert::canonical::isomorphism;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let left = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+  /// # let right = parse_str("p ; r\np a > r\nr a > r\nwrd : a");
+  /// match isomorphism(&left, &right) {
+  ///   Ok(mapping) => println!("{:?}", mapping), // [("q0", "p"), ("q1", "r")]
+  ///   Err(difference) => println!("{}", difference),
+  /// }
+  /// ```
+  pub fn isomorphism(
+    left: &ParsedFile,
+    right: &ParsedFile,
+  ) -> Result<Vec<(String, String)>, String> {
+    if !left.is_afd() || !right.is_afd() {
+      return Err("O isomorfismo só é verificado entre AFDs".to_string());
+    }
+    let alphabet: BTreeSet<String> = left
+      .get_full_alphabet()
+      .union(&right.get_full_alphabet())
+      .cloned()
+      .collect();
+
+    let start = (
+      &left.get_initial_states()[0],
+      &right.get_initial_states()[0],
+    );
+    let mut forward: HashMap<&String, &String> = HashMap::new();
+    let mut backward: HashMap<&String, &String> = HashMap::new();
+    let mut mapping: Vec<(String, String)> = Vec::new();
+    let mut queue: VecDeque<(&String, &String)> = VecDeque::new();
+    forward.insert(start.0, start.1);
+    backward.insert(start.1, start.0);
+    queue.push_back(start);
+
+    while let Some((l, r)) = queue.pop_front() {
+      mapping.push((l.to_string(), r.to_string()));
+      let l_final = left.get_finish_state().contains(l);
+      if l_final != right.get_finish_state().contains(r) {
+        return Err(format!(
+          "{} ↔ {}: somente o estado do {} autômato é final",
+          l,
+          r,
+          if l_final { "primeiro" } else { "segundo" }
+        ));
+      }
+
+      for symbol in &alphabet {
+        let (l_next, r_next) = match (self::next(left, l, symbol), self::next(right, r, symbol)) {
+          (None, None) => continue,
+          (Some(l_next), Some(r_next)) => (l_next, r_next),
+          _ => {
+            return Err(format!(
+              "{} ↔ {}: somente um deles possui transição com {}",
+              l, r, symbol
+            ))
+          }
+        };
+
+        // o par de destinos deve ser novo ou já estar mapeado um no outro
+        let conflict = match (forward.get(l_next), backward.get(r_next)) {
+          (None, None) => {
+            forward.insert(l_next, r_next);
+            backward.insert(r_next, l_next);
+            queue.push_back((l_next, r_next));
+            None
+          }
+          (Some(&mapped), _) if mapped == r_next => None,
+          (Some(&mapped), _) => Some((l_next, mapped)),
+          (None, Some(&mapped)) => Some((r_next, mapped)),
+        };
+        if let Some((state, mapped)) = conflict {
+          return Err(format!(
+            "{} ↔ {}: com {} vão para {} e {}, mas {} já corresponde a {}",
+            l, r, symbol, l_next, r_next, state, mapped
+          ));
+        }
+      }
+    }
+    Ok(mapping)
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use crate::util::file::{self, ParsedFile};
+    use std::collections::HashMap;
+
+    fn automaton(transitions: &[(&str, &str, &str)], initial: &str, finals: &[&str]) -> ParsedFile {
+      let mut states: HashMap<String, Vec<Node>> = HashMap::new();
+      for (origin, character, destination) in transitions {
+        states.entry(origin.to_string()).or_default().push(Node {
+          character: character.to_string(),
+          destination: destination.to_string(),
+        });
+      }
+      ParsedFile::new(
+        vec![initial.to_string()],
+        finals.iter().map(|e| e.to_string()).collect(),
+        states,
+        String::new(),
+      )
+    }
+
+    #[test]
+    fn renames_states_in_bfs_order() {
+      let infos = automaton(
+        &[
+          ("x", "b", "z"),
+          ("x", "a", "y"),
+          ("y", "a", "x"),
+          ("z", "a", "z"),
+        ],
+        "x",
+        &["z"],
+      );
+      let canonical = super::canonicalize(&infos);
+      assert_eq!(
+        file::serialize(&canonical),
+        "q0 ; q2\nq0 a > q1\nq0 b > q2\nq1 a > q0\nq2 a > q2\nwrd : "
+      );
+      assert_eq!(
+        super::isomorphism(&infos, &canonical),
+        Ok(vec![
+          ("x".to_string(), "q0".to_string()),
+          ("y".to_string(), "q1".to_string()),
+          ("z".to_string(), "q2".to_string()),
+        ])
+      );
+    }
+
+    #[test]
+    fn reports_first_structural_difference() {
+      let left = automaton(&[("p", "a", "q"), ("q", "a", "q")], "p", &["q"]);
+      let right = automaton(&[("p", "a", "q"), ("q", "a", "p")], "p", &["q"]);
+      assert_eq!(
+        super::isomorphism(&left, &right),
+        Err("q ↔ q: com a vão para q e p, mas q já corresponde a q".to_string())
+      );
+
+      let other = automaton(&[("p", "a", "q"), ("q", "a", "q")], "p", &["p"]);
+      assert_eq!(
+        super::isomorphism(&left, &other),
+        Err("p ↔ p: somente o estado do segundo autômato é final".to_string())
+      );
+    }
+
+    #[test]
+    fn keeps_symbols_missing_from_the_alphabet() {
+      // `b` é usado nas transições, mas não foi declarado
+      let mut infos = automaton(&[("p", "a", "p"), ("p", "b", "q")], "p", &["q"]);
+      infos.set_alphabet(Some(vec!["a".to_string()].into_iter().collect()));
+      let canonical = super::canonicalize(&infos);
+      assert_eq!(
+        file::serialize(&canonical),
+        "q0 ; q1\nalf : a\nq0 a > q0\nq0 b > q1\nwrd : "
+      );
+
+      let mut without_b = automaton(&[("p", "a", "p")], "p", &["q"]);
+      without_b.set_alphabet(Some(vec!["a".to_string()].into_iter().collect()));
+      assert!(super::isomorphism(&infos, &without_b).is_err());
+    }
+  }
+}
@@ -0,0 +1,283 @@
+use crate::afdn::Node;
+use crate::util::file::{self, Attributes, ParsedFile};
+use std::collections::BTreeMap;
+
+/// Cria o cabeçalho padrão de um arquivo dot, já com os nós terminais e a "seta" dos estados
+/// iniciais.
+///
+/// ## Arguments
+///
+/// * `infos` - Arquivo parsed, contendo as informações
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::dot::header;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+/// let mut output = header(&infos);
+/// ```
+pub fn header(infos: &ParsedFile) -> String {
+  let mut output = String::new();
+
+  // cria o cabeçalho
+  output.push_str("digraph G {\n");
+  output.push_str(
+    "\trankdir=LR;\n\toverlap=scale;\n\tsep=0.1;\n\tpad=1;\n\tnodesep=0.5;\n\tranksep=1;\n\n",
+  );
+  // posições fixas (atributo pos) somente são respeitadas pelo layout neato
+  let positioned = infos
+    .get_state_attributes()
+    .values()
+    .any(|e| e.contains_key("pos"));
+  if positioned {
+    output.push_str("\tlayout=neato;\n\n");
+  }
+  output.push_str("\tnode [style=\"rounded,filled\"]\n");
+  output.push_str("\tedge [color=\"gray\"]\n\n");
+  debug!("#Graph Header saved");
+
+  // configura os nós terminais
+  for node in infos.get_finish_state() {
+    output.push_str(&format!("\t{} [peripheries=2]\n", self::dot_id(node)));
+  }
+  debug!("#Graph Setup terminal nodes");
+
+  // atributos de exibição (@state), antes dos destaques de cada quadro
+  for (state, attributes) in infos.get_state_attributes() {
+    if !infos.get_hidden_states().contains(state) {
+      output.push_str(&format!(
+        "\t{} [{}]\n",
+        self::dot_id(state),
+        self::attributes(attributes)
+      ));
+    }
+  }
+
+  // configura a "seta" do nó inicial apontado para cada um dos estados iniciais
+  output.push_str("\n\tstart [label= \"\", shape=none,height=.0,width=.0]\n");
+  for state in infos.get_initial_states() {
+    output.push_str(&format!("\tstart -> {}\n", self::dot_id(state)));
+  }
+  debug!("#Graph Setup start node");
+
+  output
+}
+
+/// Identificador (ou valor) do graphviz entre aspas, com as aspas e as barras invertidas escapadas.
+/// Os nomes de estados e símbolos podem conter caracteres que o dot não aceita sem as aspas (e.g.
+/// `#x`, `a,b` e `alf:`).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::dot::dot_id;
+/// assert_eq!(dot_id("a,b"), "\"a,b\"");
+/// assert_eq!(dot_id("diz \"oi\""), "\"diz \\\"oi\\\"\"");
+/// ```
+pub fn dot_id(name: &str) -> String {
+  format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Converte os atributos do arquivo de entrada para o graphviz: `description` vira o `tooltip`
+/// (exibido ao passar o mouse no SVG) e a posição (`pos`) é fixada.
+fn attributes(attributes: &Attributes) -> String {
+  let converted: Vec<String> = attributes
+    .iter()
+    .map(|(key, value)| match key.as_str() {
+      "description" => format!("tooltip={}", self::dot_id(value)),
+      "pos" => format!("pos={}", self::dot_id(&format!("{}!", value))),
+      _ => format!("{}={}", key, self::dot_id(value)),
+    })
+    .collect();
+  converted.join(", ")
+}
+
+/// Atributos de uma aresta, sem os colchetes: o símbolo como `label` e os atributos da linha
+/// `@edge` (caso exista).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::dot::{dot_id, edge_attributes};
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+/// # let mut output = String::new();
+/// # let origin = "q0";
+/// # let node = &infos.get_states()[origin][0];
+/// output.push_str(&format!(
+///   "\t{} -> {} [{}]\n",
+///   dot_id(origin),
+///   dot_id(&node.destination),
+///   edge_attributes(&infos, origin, node)
+/// ));
+/// ```
+pub fn edge_attributes(infos: &ParsedFile, origin: &str, node: &Node) -> String {
+  let label = file::compact_symbols(&[node.character.as_str()]);
+  self::edge_label(infos, origin, &label, &[node])
+}
+
+/// `label` com os símbolos (já agrupados e escritos como no arquivo de entrada, e.g. `a\,b` para o
+/// símbolo `a,b`) e os atributos da primeira aresta que os possua (que
+/// podem substituir o `label`).
+fn edge_label(infos: &ParsedFile, origin: &str, label: &str, nodes: &[&Node]) -> String {
+  let found = nodes.iter().find_map(|node| {
+    infos.get_edge_attributes().get(&(
+      origin.to_string(),
+      node.character.to_string(),
+      node.destination.to_string(),
+    ))
+  });
+  match found {
+    Some(attributes) if attributes.contains_key("label") => self::attributes(attributes),
+    Some(attributes) => format!(
+      "label={}, {}",
+      self::dot_id(label),
+      self::attributes(attributes)
+    ),
+    None => format!("label={}", self::dot_id(label)),
+  }
+}
+
+/// Fecha o grafo e salva em arquivo.
+///
+/// ## Arguments
+///
+/// * `output` - Conteúdo do arquivo dot (sem o `}` final).
+/// * `filename` - Nome e local de onde salvar o arquivo dot.
+pub fn save(mut output: String, filename: &str) {
+  output.push('}');
+
+  // salva em arquivo
+  match std::fs::write(filename, output) {
+    Ok(_) => debug!("Saved file {}", filename),
+    Err(err) => panic!("{}", err),
+  }
+}
+
+/// Exporta o autômato completo, sem destaques (e.g. a máquina dada em um exercício). As
+/// transições entre os mesmos estados são desenhadas em uma única aresta (veja o
+/// [`compact_symbols`](crate::util::file::compact_symbols)).
+///
+/// ## Arguments
+///
+/// * `infos` - Arquivo parsed, contendo as informações
+/// * `filename` - Nome e local de onde salvar o arquivo dot.
+pub fn save_automaton(infos: &ParsedFile, filename: &str) {
+  let mut output = self::header(infos);
+  output.push('\n');
+
+  let mut keys: Vec<&String> = infos.get_states().keys().collect();
+  keys.sort();
+  for origin in keys {
+    let mut edges: BTreeMap<&str, Vec<&Node>> = BTreeMap::new();
+    for node in infos.get_states().get(origin).unwrap() {
+      edges.entry(&node.destination).or_default().push(node);
+    }
+    for (destination, nodes) in edges {
+      let symbols: Vec<&str> = nodes.iter().map(|e| e.character.as_str()).collect();
+      output.push_str(&format!(
+        "\t{} -> {} [{}]\n",
+        self::dot_id(origin),
+        self::dot_id(destination),
+        self::edge_label(infos, origin, &file::compact_symbols(&symbols), &nodes)
+      ));
+    }
+  }
+  self::save(output, filename);
+}
+
+/// Converte um arquivo dot para outro formato (e.g. `svg`) usando o graphviz (`dot -T<formato>`).
+/// Caso o graphviz não esteja instalado, apenas exibe um aviso e mantém o arquivo dot.
+///
+/// ## Arguments
+///
+/// * `filename` - Arquivo dot de origem.
+/// * `format` - Formato de saída (extensão).
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use afdn_animator::dot::render;
+/// render("./dot/arvore.dot", "svg"); // gera ./dot/arvore.svg
+/// ```
+pub fn render(filename: &str, format: &str) {
+  let output = format!("{}.{}", filename.trim_end_matches(".dot"), format);
+  let status = std::process::Command::new("dot")
+    .arg(format!("-T{}", format))
+    .arg(filename)
+    .arg("-o")
+    .arg(&output)
+    .status();
+
+  match status {
+    Ok(status) if status.success() => debug!("Rendered {}", output),
+    Ok(status) => eprintln!("O graphviz falhou ao gerar {} ({})", output, status),
+    Err(err) => eprintln!(
+      "Não foi possível executar o graphviz (dot) para gerar {}: {}",
+      output, err
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::util::file::parse_str;
+
+  #[test]
+  fn draws_a_start_arrow_to_each_initial_state() {
+    let infos = parse_str("p0 q0 ; p1\np0 a > p1\nq0 b > p1\nwrd : a");
+    let header = super::header(&infos);
+    assert!(header.contains("\tstart -> \"p0\"\n"));
+    assert!(header.contains("\tstart -> \"q0\"\n"));
+    assert_eq!(header.matches("start ->").count(), 2);
+  }
+
+  #[test]
+  fn quotes_names_in_the_exported_graph() {
+    let mut infos = parse_str("\\#x ; a,b\n\\#x 0\\-9 > a,b\n@state a,b label=fim\nwrd : ");
+    infos.set_edge_attribute(("#x", "0-9", "a,b"), "description", "diz \"oi\"");
+    let filename = std::env::temp_dir().join("afdn_quoted_names.dot");
+    super::save_automaton(&infos, &filename.to_string_lossy());
+
+    let content = std::fs::read_to_string(&filename).unwrap();
+    assert!(content.contains("\t\"a,b\" [peripheries=2]\n"));
+    assert!(content.contains("\t\"a,b\" [label=\"fim\"]\n"));
+    assert!(content.contains("\tstart -> \"#x\"\n"));
+    assert!(
+      content.contains("\t\"#x\" -> \"a,b\" [label=\"0\\\\-9\", tooltip=\"diz \\\"oi\\\"\"]\n")
+    );
+  }
+
+  #[test]
+  fn escapes_literal_symbols_in_joined_labels() {
+    // o símbolo `,` ao lado de `a` não pode ser lido como dois símbolos vazios
+    let infos = parse_str("q0 ; q1\nq0 , > q1\nq0 a > q1\nq0 a\\,b > q0\nwrd : ");
+    let filename = std::env::temp_dir().join("afdn_joined_labels.dot");
+    super::save_automaton(&infos, &filename.to_string_lossy());
+
+    let content = std::fs::read_to_string(&filename).unwrap();
+    assert!(content.contains("\t\"q0\" -> \"q1\" [label=\"\\\\,,a\"]\n"));
+    assert!(content.contains("\t\"q0\" -> \"q0\" [label=\"a\\\\,b\"]\n"));
+  }
+
+  #[test]
+  fn exports_escaped_names_after_a_round_trip() {
+    use crate::util::file::serialize;
+
+    // estados que, sem o escape, seriam lidos como seções ou comentários
+    let infos = parse_str("alf\\: ; wrd\\:\nalf\\: \\# > wrd\\:\nwrd\\: a > alf\\:\nwrd : ");
+    let again = parse_str(&serialize(&infos));
+    let export = |infos: &crate::util::file::ParsedFile, name: &str| {
+      let filename = std::env::temp_dir().join(name);
+      super::save_automaton(infos, &filename.to_string_lossy());
+      std::fs::read_to_string(&filename).unwrap()
+    };
+
+    let content = export(&infos, "afdn_escaped_names.dot");
+    assert_eq!(content, export(&again, "afdn_escaped_names_again.dot"));
+    assert!(content.contains("\tstart -> \"alf:\"\n"));
+    assert!(content.contains("\t\"alf:\" -> \"wrd:\" [label=\"\\\\#\"]\n"));
+    assert!(content.contains("\t\"wrd:\" -> \"alf:\" [label=\"a\"]\n"));
+  }
+}
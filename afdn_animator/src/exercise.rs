@@ -0,0 +1,332 @@
+use crate::afdn::{Node, NodeVec};
+use crate::check;
+use crate::convert::{canonical::canonicalize, determinize::determinize, minimize::minimize};
+use crate::dot;
+use crate::generate::{self, Config};
+use crate::language;
+use crate::util::file::{self, ParsedFile};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// # Kind
+/// Tipos de exercício que podem ser gerados.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+  /// Construir um AFD para as palavras terminadas no padrão.
+  EndsWith(Vec<String>),
+  /// Construir um AFD para as palavras que contém o padrão.
+  Contains(Vec<String>),
+  /// Determinizar o AFN dado.
+  Determinize,
+  /// Minimizar o AFD dado.
+  Minimize,
+}
+
+/// # Exercise
+/// Um exercício gerado: enunciado, máquina dada (se houver), solução de referência e chave de
+/// correção (o AFD mínimo da linguagem pedida, na forma canônica).
+#[derive(Debug)]
+pub struct Exercise {
+  pub kind: Kind,
+  pub statement: String,
+  pub given: Option<ParsedFile>,
+  pub solution: ParsedFile,
+  pub key: ParsedFile,
+}
+
+/// # Grade
+/// Resultado da correção de uma submissão.
+#[derive(Debug, PartialEq)]
+pub enum Grade {
+  Correct,
+  /// A submissão reconhece outra linguagem. Contém a menor palavra em que ela diverge da chave
+  /// e se a submissão a aceita.
+  Wrong(Vec<String>, bool),
+}
+
+/// Sorteia um tipo de exercício, com padrões de 2 ou 3 símbolos.
+pub fn random_kind<R: Rng>(rng: &mut R, alphabet: &[String]) -> Kind {
+  let len = rng.gen_range(2..=3);
+  let pattern: Vec<String> = (0..len)
+    .filter_map(|_| alphabet.choose(rng).cloned())
+    .collect();
+  match rng.gen_range(0..4) {
+    0 => Kind::EndsWith(pattern),
+    1 => Kind::Contains(pattern),
+    2 => Kind::Determinize,
+    _ => Kind::Minimize,
+  }
+}
+
+/// AFN para as palavras que contém (ou terminam em) `pattern`: `p0` lê qualquer símbolo e a
+/// cadeia `p0 -> p1 -> ... -> pn` reconhece o padrão.
+fn pattern_automaton(alphabet: &[String], pattern: &[String], ends_with: bool) -> ParsedFile {
+  let state = |i: usize| format!("p{}", i);
+  let mut states: HashMap<String, NodeVec> = HashMap::new();
+  let loops = |states: &mut HashMap<String, NodeVec>, i: usize| {
+    for symbol in alphabet {
+      states.entry(state(i)).or_default().push(Node {
+        character: symbol.to_string(),
+        destination: state(i),
+      });
+    }
+  };
+  loops(&mut states, 0);
+  for (i, symbol) in pattern.iter().enumerate() {
+    states.entry(state(i)).or_default().push(Node {
+      character: symbol.to_string(),
+      destination: state(i + 1),
+    });
+  }
+  if !ends_with {
+    loops(&mut states, pattern.len());
+  }
+  ParsedFile::new(
+    vec![state(0)],
+    vec![state(pattern.len())],
+    states,
+    String::new(),
+  )
+}
+
+/// Quantidade máxima de máquinas sorteadas até obter uma adequada ao exercício (um AFN que não
+/// seja determinístico ou um AFD que não seja mínimo).
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Sorteia máquinas até que uma satisfaça `suitable`, desistindo após [`MAX_ATTEMPTS`] tentativas
+/// (e.g. um único estado nunca gera um AFN não determinístico).
+fn generate_until<R, F>(config: &Config, rng: &mut R, suitable: F) -> Option<ParsedFile>
+where
+  R: Rng,
+  F: Fn(&ParsedFile) -> bool,
+{
+  (0..MAX_ATTEMPTS)
+    .map(|_| generate::generate(config, rng))
+    .find(|e| suitable(e))
+}
+
+/// Gera um exercício do tipo pedido. Falha caso a configuração não permita gerar a máquina dada
+/// (e.g. determinizar um AFN com um único estado).
+///
+/// ## Arguments
+///
+/// * `kind` - Tipo do exercício.
+/// * `config` - Configuração do gerador (alfabeto e tamanho da máquina dada).
+/// * `rng` - Gerador de números aleatórios (permite fixar a semente).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::exercise::{create, Kind};
+/// # use afdn_animator::generate::Config;
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+/// let exercise = create(Kind::EndsWith(vec!["a".into(), "b".into()]), &Config::default(), &mut rng)?;
+/// # Ok::<(), String>(())
+/// ```
+pub fn create<R: Rng>(kind: Kind, config: &Config, rng: &mut R) -> Result<Exercise, String> {
+  let alphabet = config.alphabet.join(", ");
+  let (statement, given, solution) = match &kind {
+    Kind::EndsWith(pattern) | Kind::Contains(pattern) => {
+      let ends_with = matches!(kind, Kind::EndsWith(_));
+      let statement = format!(
+        "Construa um AFD sobre {{{}}} que aceite as palavras {} \"{}\".",
+        alphabet,
+        if ends_with {
+          "terminadas em"
+        } else {
+          "que contém"
+        },
+        pattern.join("")
+      );
+      let nfa = self::pattern_automaton(&config.alphabet, pattern, ends_with);
+      (statement, None, minimize(&nfa))
+    }
+    Kind::Determinize => {
+      let config = Config {
+        deterministic: false,
+        ..config.clone()
+      };
+      // garante que a máquina dada é, de fato, não determinística
+      let nfa = self::generate_until(&config, rng, |e| !e.is_afd()).ok_or(format!(
+        "Não foi possível gerar um AFN não determinístico com {} estado(s)",
+        config.states
+      ))?;
+      let statement = format!(
+        "Construa um AFD equivalente ao AFN dado (sobre {{{}}}), usando a construção dos subconjuntos.",
+        alphabet
+      );
+      let solution = determinize(&nfa);
+      (statement, Some(nfa), solution)
+    }
+    Kind::Minimize => {
+      let config = Config {
+        deterministic: true,
+        ..config.clone()
+      };
+      // garante que a máquina dada ainda não é a mínima
+      let afd = self::generate_until(&config, rng, |e| {
+        minimize(e).get_all_states().len() < e.get_all_states().len()
+      })
+      .ok_or(format!(
+        "Não foi possível gerar um AFD que não seja mínimo com {} estado(s)",
+        config.states
+      ))?;
+      let statement = format!(
+        "Construa o AFD mínimo equivalente ao AFD dado (sobre {{{}}}).",
+        alphabet
+      );
+      let solution = minimize(&afd);
+      (statement, Some(afd), solution)
+    }
+  };
+
+  let mut key = canonicalize(&minimize(&solution));
+  key.set_alphabet(Some(config.alphabet.iter().cloned().collect()));
+  // palavra de exemplo: a menor aceita (se existir)
+  let word = language::shortest_accepted(&key).unwrap_or_default();
+  key.set_word(file::join_tokens(&word));
+
+  let mut solution = solution;
+  solution.set_alphabet(key.get_declared_alphabet().cloned());
+  solution.set_word(key.get_word().to_string());
+
+  Ok(Exercise {
+    kind,
+    statement,
+    given,
+    solution,
+    key,
+  })
+}
+
+/// Salva o exercício no diretório:
+/// - `enunciado.txt`: o enunciado;
+/// - `dado.txt`, `dado.dot` e `dado.svg`: a máquina dada (somente se houver);
+/// - `gabarito.txt`: a solução de referência;
+/// - `chave.txt`: a chave de correção, usada por `--corrigir=<chave>`.
+pub fn save(exercise: &Exercise, dir: &str) {
+  let write = |name: &str, content: String| {
+    let filename = format!("{}/{}", dir, name);
+    match std::fs::write(&filename, content) {
+      Ok(_) => debug!("Saved file {}", filename),
+      Err(err) => panic!("{}", err),
+    }
+  };
+  if let Err(err) = std::fs::create_dir_all(dir) {
+    panic!("{}", err);
+  }
+
+  write("enunciado.txt", format!("{}\n", exercise.statement));
+  if let Some(given) = &exercise.given {
+    write("dado.txt", file::serialize(given));
+    let filename = format!("{}/dado.dot", dir);
+    dot::save_automaton(given, &filename);
+    dot::render(&filename, "svg");
+  }
+  write("gabarito.txt", file::serialize(&exercise.solution));
+  write("chave.txt", file::serialize(&exercise.key));
+}
+
+/// Corrige a submissão comparando sua linguagem com a da chave. Uma submissão não determinística
+/// é determinizada antes da comparação.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use afdn_animator::exercise::{grade, Grade};
+/// # use afdn_animator::util::file;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+/// let grade: Grade = grade(&infos, &file::parse("exercicio/chave.txt"));
+/// ```
+pub fn grade(submission: &ParsedFile, key: &ParsedFile) -> Grade {
+  let determinized;
+  let submission = if submission.is_afd() {
+    submission
+  } else {
+    determinized = determinize(submission);
+    &determinized
+  };
+  match language::counterexample(submission, key) {
+    Some(word) => {
+      let accepted = check::accepts(submission, &word);
+      Grade::Wrong(word, accepted)
+    }
+    None => Grade::Correct,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Grade, Kind};
+  use crate::generate::Config;
+  use crate::util::file;
+  use rand::rngs::StdRng;
+  use rand::SeedableRng;
+
+  fn pattern(symbols: &str) -> Vec<String> {
+    symbols.chars().map(|e| e.to_string()).collect()
+  }
+
+  #[test]
+  fn grades_by_language_equivalence() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let exercise =
+      super::create(Kind::EndsWith(pattern("ab")), &Config::default(), &mut rng).unwrap();
+    assert_eq!(exercise.key.get_all_states().len(), 3);
+    assert_eq!(exercise.key.get_word(), "ab");
+    assert_eq!(
+      super::grade(&exercise.solution, &exercise.key),
+      Grade::Correct
+    );
+
+    // "contém ab" aceita "aba", que não termina em "ab"
+    let other = super::create(Kind::Contains(pattern("ab")), &Config::default(), &mut rng).unwrap();
+    assert_eq!(
+      super::grade(&other.key, &exercise.key),
+      Grade::Wrong(pattern("aba"), true)
+    );
+
+    // um AFN também é corrigido pela linguagem
+    let nfa = file::parse_str("q0 ; q2\nq0 a > q0\nq0 b > q0\nq0 a > q1\nq1 b > q2\nwrd : ab");
+    assert_eq!(super::grade(&nfa, &exercise.key), Grade::Correct);
+    let wrong = file::parse_str("q0 ; q1\nq0 a > q0\nq0 b > q0\nq0 b > q1\nwrd : ab");
+    assert_eq!(
+      super::grade(&wrong, &exercise.key),
+      Grade::Wrong(pattern("b"), true)
+    );
+  }
+
+  #[test]
+  fn keys_accept_the_given_machine_language() {
+    let mut rng = StdRng::seed_from_u64(3);
+    for kind in [Kind::Determinize, Kind::Minimize] {
+      let exercise = super::create(kind, &Config::default(), &mut rng).unwrap();
+      let given = exercise.given.unwrap();
+      assert_eq!(crate::language::counterexample(&given, &exercise.key), None);
+      assert_eq!(
+        super::grade(&exercise.solution, &exercise.key),
+        Grade::Correct
+      );
+      // a chave pode ser lida de volta do arquivo
+      assert!(file::serialize(&exercise.key).contains("alf : a b"));
+    }
+  }
+
+  #[test]
+  fn given_machine_is_not_already_solved() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let exercise = super::create(Kind::Minimize, &Config::default(), &mut rng).unwrap();
+    let given = exercise.given.unwrap();
+    assert!(exercise.solution.get_all_states().len() < given.get_all_states().len());
+
+    // um único estado nunca gera um AFN não determinístico
+    let single = Config {
+      states: 1,
+      ..Config::default()
+    };
+    assert!(super::create(Kind::Determinize, &single, &mut rng).is_err());
+  }
+}
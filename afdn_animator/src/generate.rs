@@ -0,0 +1,252 @@
+use crate::afdn::{afn::LAMBDA, Node, NodeVec};
+use crate::check;
+use crate::util::file::{self, ParsedFile};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeSet, HashMap};
+
+/// # Config
+/// Parâmetros do gerador de autômatos aleatórios.
+#[derive(Debug, Clone)]
+pub struct Config {
+  /// Quantidade de estados (`q0`, `q1`, ...). `q0` é sempre o estado inicial.
+  pub states: usize,
+  pub alphabet: Vec<String>,
+  /// Gera um AFD (no máximo uma transição por símbolo e sem lambda) ou um AFN.
+  pub deterministic: bool,
+  /// Probabilidade de cada par `(estado, símbolo)` ganhar uma transição (além das que garantem a
+  /// alcançabilidade). Em AFNs, a mesma probabilidade é usada para transições adicionais. Deve estar
+  /// entre 0 e 1.
+  pub density: f64,
+  /// Probabilidade de cada estado ganhar uma transição lambda (somente AFNs), entre 0 e 1.
+  pub lambda: f64,
+  /// Quantidade de estados finais.
+  pub finals: usize,
+  /// Tamanho máximo da palavra de exemplo (`wrd :`).
+  pub word_len: usize,
+}
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      states: 4,
+      alphabet: vec!["a".to_string(), "b".to_string()],
+      deterministic: true,
+      density: 0.5,
+      lambda: 0.1,
+      finals: 1,
+      word_len: 6,
+    }
+  }
+}
+
+/// Lê o alfabeto do gerador a partir de uma lista separada por vírgulas (`a,b,c`). Assim como na
+/// linha `alf :`, os símbolos não podem ser vazios nem conter espaços, e `/` (lambda) não é um
+/// símbolo do alfabeto.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::generate::parse_alphabet;
+/// assert_eq!(parse_alphabet("a,b").unwrap(), vec!["a", "b"]);
+/// assert!(parse_alphabet("a,/").is_err());
+/// ```
+pub fn parse_alphabet(list: &str) -> Result<Vec<String>, String> {
+  let mut alphabet: Vec<String> = Vec::new();
+  for symbol in list.split(',') {
+    if symbol.is_empty() || symbol.contains(char::is_whitespace) {
+      return Err(format!(
+        "Símbolo vazio ou com espaços no alfabeto: {:?}",
+        list
+      ));
+    }
+    if symbol == LAMBDA {
+      return Err(format!(
+        "{} é lambda e não pode ser um símbolo do alfabeto",
+        LAMBDA
+      ));
+    }
+    if !alphabet.iter().any(|e| e == symbol) {
+      alphabet.push(symbol.to_string());
+    }
+  }
+  Ok(alphabet)
+}
+
+fn state(i: usize) -> String {
+  format!("q{}", i)
+}
+
+/// Gera um autômato aleatório em que todos os estados são alcançáveis a partir do inicial.
+///
+/// A alcançabilidade é garantida por uma árvore geradora: cada estado `q<i>` recebe uma transição
+/// vinda de algum estado anterior. Depois, as transições extras são sorteadas conforme a
+/// densidade. A palavra de exemplo é obtida por um passeio aleatório, então tende a ser aceita.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::generate::{generate, Config};
+/// # use afdn_animator::util::file;
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+/// let infos = generate(&Config::default(), &mut rng);
+/// println!("{}", file::serialize(&infos));
+/// ```
+pub fn generate<R: Rng>(config: &Config, rng: &mut R) -> ParsedFile {
+  if config.states == 0 || config.alphabet.is_empty() {
+    panic!("O autômato precisa de ao menos um estado e um símbolo");
+  }
+
+  let mut states: HashMap<String, NodeVec> = HashMap::new();
+  let add = |states: &mut HashMap<String, NodeVec>, from: usize, character: &str, to: usize| {
+    let node = Node {
+      character: character.to_string(),
+      destination: state(to),
+    };
+    let nodes = states.entry(state(from)).or_default();
+    if !nodes.contains(&node) {
+      nodes.push(node);
+    }
+  };
+  let used = |states: &HashMap<String, NodeVec>, from: usize, character: &str| {
+    states
+      .get(&state(from))
+      .is_some_and(|nodes| nodes.iter().any(|e| e.character == character))
+  };
+
+  // árvore geradora: liga cada estado a um anterior (que, no AFD, ainda tenha símbolo livre).
+  // Há sempre um par livre, pois os `i` estados anteriores têm `i * |alfabeto|` pares e só
+  // `i - 1` foram usados
+  for to in 1..config.states {
+    let free: Vec<(usize, &String)> = (0..to)
+      .flat_map(|from| config.alphabet.iter().map(move |c| (from, c)))
+      .filter(|(from, c)| !config.deterministic || !used(&states, *from, c))
+      .collect();
+    let (from, character) = *free.choose(rng).unwrap();
+    add(&mut states, from, character, to);
+  }
+
+  // transições extras
+  for from in 0..config.states {
+    for character in &config.alphabet {
+      if config.deterministic && used(&states, from, character) {
+        continue;
+      }
+      if rng.gen_bool(config.density) {
+        add(
+          &mut states,
+          from,
+          character,
+          rng.gen_range(0..config.states),
+        );
+      }
+      if !config.deterministic && rng.gen_bool(config.density / 2.0) {
+        add(
+          &mut states,
+          from,
+          character,
+          rng.gen_range(0..config.states),
+        );
+      }
+    }
+    if !config.deterministic && config.states > 1 && rng.gen_bool(config.lambda) {
+      let to = (from + rng.gen_range(1..config.states)) % config.states;
+      add(&mut states, from, LAMBDA, to);
+    }
+  }
+
+  let all: Vec<usize> = (0..config.states).collect();
+  let mut finals: Vec<String> = all
+    .choose_multiple(rng, config.finals.min(config.states))
+    .map(|e| state(*e))
+    .collect();
+  finals.sort_by_key(|e| e[1..].parse::<usize>().unwrap());
+
+  let mut infos = ParsedFile::new(vec![state(0)], finals, states, String::new());
+  let alphabet: BTreeSet<String> = config.alphabet.iter().cloned().collect();
+  infos.set_alphabet(Some(alphabet));
+
+  let word = check::walk_word(rng, &infos, config.word_len);
+  infos.set_word(file::join_tokens(&word));
+  infos
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Config;
+  use crate::language;
+  use rand::rngs::StdRng;
+  use rand::SeedableRng;
+
+  #[test]
+  fn generates_reachable_dfas() {
+    let config = Config {
+      states: 8,
+      density: 0.3,
+      finals: 3,
+      ..Config::default()
+    };
+    for seed in 0..20 {
+      let infos = super::generate(&config, &mut StdRng::seed_from_u64(seed));
+      assert!(infos.is_afd());
+      assert_eq!(infos.get_finish_state().len(), 3);
+      assert_eq!(language::reachable(&infos).len(), 8);
+    }
+  }
+
+  #[test]
+  fn generates_nfas_with_lambda() {
+    let config = Config {
+      states: 6,
+      deterministic: false,
+      density: 0.8,
+      lambda: 1.0,
+      ..Config::default()
+    };
+    let infos = super::generate(&config, &mut StdRng::seed_from_u64(7));
+    assert!(!infos.is_afd());
+    assert_eq!(language::reachable(&infos).len(), 6);
+  }
+
+  #[test]
+  fn reproduces_the_benchmark_fixtures() {
+    // mesmas opções documentadas em benches/engines.rs (--semente=44); o afd_2000 fica de fora
+    // por ser lento sem otimizações
+    let fixtures = [
+      ("afn_30.txt", false, 30, 0.4, 0.3, 5),
+      ("afn_lambda_200.txt", false, 200, 0.3, 0.8, 20),
+    ];
+    for (name, deterministic, states, density, lambda, finals) in fixtures.iter() {
+      let config = Config {
+        states: *states,
+        deterministic: *deterministic,
+        density: *density,
+        lambda: *lambda,
+        finals: *finals,
+        ..Config::default()
+      };
+      let infos = super::generate(&config, &mut StdRng::seed_from_u64(44));
+      let expected = std::fs::read_to_string(format!(
+        "{}/benches/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+      ))
+      .unwrap();
+      assert_eq!(
+        crate::util::file::serialize(&infos).trim_end(),
+        expected.trim_end(),
+        "{}",
+        name
+      );
+    }
+  }
+
+  #[test]
+  fn rejects_invalid_alphabet_symbols() {
+    assert_eq!(super::parse_alphabet("a,b,a").unwrap(), vec!["a", "b"]);
+    assert!(super::parse_alphabet("a,/").is_err());
+    assert!(super::parse_alphabet("a,,b").is_err());
+    assert!(super::parse_alphabet("").is_err());
+    assert!(super::parse_alphabet("a,b c").is_err());
+  }
+}
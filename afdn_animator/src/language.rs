@@ -0,0 +1,510 @@
+use crate::automaton::{Automaton, StateId, SymbolId};
+use crate::util::file::ParsedFile;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// # Size
+/// Classificação da linguagem reconhecida pelo autômato.
+#[derive(Debug, PartialEq)]
+pub enum Size {
+  Empty,
+  /// Linguagem finita, com a quantidade de palavras.
+  Finite(usize),
+  Infinite,
+}
+
+/// Estados a partir dos quais é possível atingir um estado final (busca no grafo invertido).
+///
+/// ## Arguments
+///
+/// * `infos` - O arquivo de entrada lido e mapeado.
+pub fn co_reachable(infos: &ParsedFile) -> BTreeSet<String> {
+  let automaton = Automaton::from_parsed(infos);
+  self::names(&automaton, &automaton.co_reachable())
+}
+
+/// Estados alcançáveis a partir dos estados iniciais.
+///
+/// ## Arguments
+///
+/// * `infos` - O arquivo de entrada lido e mapeado.
+pub fn reachable(infos: &ParsedFile) -> BTreeSet<String> {
+  let automaton = Automaton::from_parsed(infos);
+  self::names(&automaton, &automaton.reachable(automaton.initial_states()))
+}
+
+/// Nomes dos estados do conjunto.
+fn names(automaton: &Automaton, states: &BTreeSet<StateId>) -> BTreeSet<String> {
+  states
+    .iter()
+    .map(|e| automaton.state_name(*e).to_string())
+    .collect()
+}
+
+/// Conjunto inicial da simulação por conjuntos: o fecho lambda dos estados iniciais.
+fn start(automaton: &Automaton) -> BTreeSet<StateId> {
+  automaton.closure(automaton.initial_states().clone())
+}
+
+fn accepting(automaton: &Automaton, active: &BTreeSet<StateId>) -> bool {
+  active.iter().any(|e| automaton.is_final(*e))
+}
+
+/// Busca das palavras aceitas de um tamanho fixo. Os conjuntos de estados ativos são os estados
+/// do AFD equivalente, então a pergunta "é possível aceitar com exatamente `n` símbolos?" é
+/// respondida uma única vez para cada par `(conjunto, n)`.
+struct Words<'a> {
+  automaton: &'a Automaton,
+  memo: HashMap<(BTreeSet<StateId>, usize), bool>,
+}
+impl<'a> Words<'a> {
+  /// Verifica se, a partir de `active`, alguma palavra com exatamente `steps` símbolos é aceita.
+  fn accepts_in(&mut self, active: &BTreeSet<StateId>, steps: usize) -> bool {
+    if steps == 0 {
+      return self::accepting(self.automaton, active);
+    }
+    if let Some(&known) = self.memo.get(&(active.clone(), steps)) {
+      return known;
+    }
+    let found = (0..self.automaton.alphabet().len()).any(|symbol| {
+      let next = self.automaton.step(active, symbol);
+      !next.is_empty() && self.accepts_in(&next, steps - 1)
+    });
+    self.memo.insert((active.clone(), steps), found);
+    found
+  }
+
+  /// Estende `word` (em ordem) somente com os símbolos que ainda permitem aceitar com `remaining`
+  /// símbolos, i.e, todo ramo visitado gera uma palavra.
+  fn collect(
+    &mut self,
+    active: &BTreeSet<StateId>,
+    remaining: usize,
+    word: &mut Vec<String>,
+    words: &mut Vec<Vec<String>>,
+    max_count: usize,
+  ) {
+    if remaining == 0 {
+      words.push(word.to_vec());
+      return;
+    }
+    for symbol in 0..self.automaton.alphabet().len() {
+      if words.len() >= max_count {
+        return;
+      }
+      let next = self.automaton.step(active, symbol);
+      if !next.is_empty() && self.accepts_in(&next, remaining - 1) {
+        word.push(self.automaton.alphabet()[symbol].to_string());
+        self.collect(&next, remaining - 1, word, words, max_count);
+        word.pop();
+      }
+    }
+  }
+}
+
+/// Enumera as palavras aceitas em ordem *shortlex* (primeiro por tamanho e, depois, pela ordem do
+/// alfabeto), até um tamanho máximo e/ou uma quantidade máxima de palavras.
+/// Para cada tamanho, somente os prefixos que ainda podem ser aceitos com aquele tamanho são
+/// estendidos, então o trabalho é proporcional à quantidade de palavras retornadas.
+///
+/// ## Arguments
+///
+/// * `infos` - O arquivo de entrada lido e mapeado.
+/// * `max_len` - Tamanho máximo (em símbolos) das palavras.
+/// * `max_count` - Quantidade máxima de palavras retornadas.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::language::enumerate;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// let words = enumerate(&infos, 10, 20); // [["a"], ["a", "a"], ...]
+/// # assert_eq!(words[1], vec!["a", "a"]);
+/// ```
+pub fn enumerate(infos: &ParsedFile, max_len: usize, max_count: usize) -> Vec<Vec<String>> {
+  let automaton = Automaton::from_parsed(infos);
+  let start = self::start(&automaton);
+  let mut search = Words {
+    automaton: &automaton,
+    memo: HashMap::new(),
+  };
+
+  let mut words: Vec<Vec<String>> = Vec::new();
+  for len in 0..=max_len {
+    if words.len() >= max_count {
+      break;
+    }
+    if search.accepts_in(&start, len) {
+      search.collect(&start, len, &mut Vec::new(), &mut words, max_count);
+    }
+  }
+
+  debug!("Enumerated words: {:?}", &words);
+  words
+}
+
+/// Verifica se a linguagem é vazia, finita (e o seu tamanho) ou infinita.
+///
+/// São considerados somente os estados úteis (alcançáveis e que atingem um estado final). A
+/// linguagem é infinita se, e somente se, existir um ciclo entre eles que consuma algum símbolo.
+/// Caso seja finita, os conjuntos de estados ativos (os estados do AFD equivalente) não formam
+/// ciclos e cada palavra corresponde a um único caminho até um conjunto de aceitação, então basta
+/// contar os caminhos. A contagem satura em `usize::MAX`.
+///
+/// ## Arguments
+///
+/// * `infos` - O arquivo de entrada lido e mapeado.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::language::{size, Size};
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+/// match size(&infos) { Size::Finite(n) => println!("{} palavras", n), _ => {} }
+/// ```
+pub fn size(infos: &ParsedFile) -> Size {
+  let automaton = Automaton::from_parsed(infos);
+  let start = self::start(&automaton);
+  let useful: BTreeSet<StateId> = automaton
+    .reachable(&start)
+    .intersection(&automaton.co_reachable())
+    .copied()
+    .collect();
+  debug!("Useful states: {:?}", &useful);
+
+  let start: BTreeSet<StateId> = start.intersection(&useful).copied().collect();
+  if start.is_empty() {
+    return Size::Empty;
+  }
+
+  if self::has_cycle(&automaton, &useful) {
+    return Size::Infinite;
+  }
+
+  let mut counts: HashMap<BTreeSet<StateId>, usize> = HashMap::new();
+  Size::Finite(self::count_words(&automaton, &useful, start, &mut counts))
+}
+
+/// Quantidade de palavras aceitas a partir do conjunto `active`, passando somente pelos estados
+/// `useful` (sem ciclos entre eles), com aritmética saturada.
+fn count_words(
+  automaton: &Automaton,
+  useful: &BTreeSet<StateId>,
+  active: BTreeSet<StateId>,
+  counts: &mut HashMap<BTreeSet<StateId>, usize>,
+) -> usize {
+  if let Some(&count) = counts.get(&active) {
+    return count;
+  }
+  let mut count = usize::from(self::accepting(automaton, &active));
+  for symbol in 0..automaton.alphabet().len() {
+    let next: BTreeSet<StateId> = automaton
+      .step(&active, symbol)
+      .intersection(useful)
+      .copied()
+      .collect();
+    if !next.is_empty() {
+      count = count.saturating_add(self::count_words(automaton, useful, next, counts));
+    }
+  }
+  counts.insert(active, count);
+  count
+}
+
+/// Busca em largura nos conjuntos de estados ativos (os estados do AFD equivalente) pela menor
+/// palavra (em ordem shortlex) que leva a um conjunto que satisfaz `wanted`. O conjunto vazio é o
+/// estado armadilha.
+fn shortest<F>(automaton: &Automaton, wanted: F) -> Option<Vec<String>>
+where
+  F: Fn(&BTreeSet<StateId>) -> bool,
+{
+  let start = self::start(automaton);
+  if wanted(&start) {
+    return Some(Vec::new());
+  }
+
+  let mut visited: BTreeSet<BTreeSet<StateId>> = BTreeSet::new();
+  let mut queue: VecDeque<(BTreeSet<StateId>, Vec<String>)> = VecDeque::new();
+  visited.insert(start.clone());
+  queue.push_back((start, Vec::new()));
+
+  while let Some((active, word)) = queue.pop_front() {
+    for (symbol, name) in automaton.alphabet().iter().enumerate() {
+      let next = automaton.step(&active, symbol);
+      let mut extended = word.to_vec();
+      extended.push(name.to_string());
+      if wanted(&next) {
+        return Some(extended);
+      }
+      if !next.is_empty() && visited.insert(next.clone()) {
+        queue.push_back((next, extended));
+      }
+    }
+  }
+  None
+}
+
+/// Menor palavra aceita (em ordem shortlex), caso a linguagem não seja vazia.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::language::shortest_accepted;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// let word: Option<Vec<String>> = shortest_accepted(&infos);
+/// ```
+pub fn shortest_accepted(infos: &ParsedFile) -> Option<Vec<String>> {
+  let automaton = Automaton::from_parsed(infos);
+  self::shortest(&automaton, |active| self::accepting(&automaton, active))
+}
+
+/// Menor palavra sobre o alfabeto que é rejeitada (em ordem shortlex), caso o autômato não aceite
+/// todas as palavras.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::language::shortest_rejected;
+/// # use afdn_animator::parsers::parse_str;
+/// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// let word: Option<Vec<String>> = shortest_rejected(&infos);
+/// ```
+pub fn shortest_rejected(infos: &ParsedFile) -> Option<Vec<String>> {
+  let automaton = Automaton::from_parsed(infos);
+  self::shortest(&automaton, |active| !self::accepting(&automaton, active))
+}
+
+/// Verifica se os dois autômatos reconhecem a mesma linguagem (busca em largura no produto dos
+/// AFDs equivalentes). Retorna a menor palavra (em ordem shortlex) aceita por somente um deles ou
+/// `None`, caso sejam equivalentes.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use afdn_animator::language::counterexample;
+/// # use afdn_animator::parsers::parse_str;
+/// # let submission = parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a");
+/// # let reference = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+/// if let Some(word) = counterexample(&submission, &reference) { println!("{:?}", word); }
+/// ```
+pub fn counterexample(left: &ParsedFile, right: &ParsedFile) -> Option<Vec<String>> {
+  let left = Automaton::from_parsed(left);
+  let right = Automaton::from_parsed(right);
+  let alphabet: BTreeSet<&String> = left.alphabet().iter().chain(right.alphabet()).collect();
+  // símbolo em cada autômato (fora do alfabeto de um deles, leva à sua armadilha)
+  let symbols: Vec<(&String, Option<SymbolId>, Option<SymbolId>)> = alphabet
+    .into_iter()
+    .map(|e| (e, left.symbol_id(e), right.symbol_id(e)))
+    .collect();
+  let step =
+    |automaton: &Automaton, active: &BTreeSet<StateId>, symbol: Option<SymbolId>| match symbol {
+      Some(symbol) => automaton.step(active, symbol),
+      None => BTreeSet::new(),
+    };
+
+  type Pair = (BTreeSet<StateId>, BTreeSet<StateId>);
+  let start: Pair = (self::start(&left), self::start(&right));
+  let mut visited: BTreeSet<Pair> = BTreeSet::new();
+  let mut queue: VecDeque<(Pair, Vec<String>)> = VecDeque::new();
+  visited.insert(start.clone());
+  queue.push_back((start, Vec::new()));
+
+  while let Some(((l, r), word)) = queue.pop_front() {
+    if self::accepting(&left, &l) != self::accepting(&right, &r) {
+      return Some(word);
+    }
+    for (name, left_symbol, right_symbol) in &symbols {
+      let next = (
+        step(&left, &l, *left_symbol),
+        step(&right, &r, *right_symbol),
+      );
+      if visited.insert(next.clone()) {
+        let mut extended = word.to_vec();
+        extended.push(name.to_string());
+        queue.push_back((next, extended));
+      }
+    }
+  }
+  None
+}
+
+/// Busca em profundidade por um ciclo entre os estados `allowed`, andando somente pelos passos que
+/// consomem um símbolo (já com o fecho lambda).
+fn has_cycle(automaton: &Automaton, allowed: &BTreeSet<StateId>) -> bool {
+  // 0 = não visitado, 1 = na pilha, 2 = finalizado
+  let mut color: HashMap<StateId, u8> = HashMap::new();
+
+  fn visit(
+    automaton: &Automaton,
+    allowed: &BTreeSet<StateId>,
+    state: StateId,
+    color: &mut HashMap<StateId, u8>,
+  ) -> bool {
+    color.insert(state, 1);
+    let single: BTreeSet<StateId> = std::iter::once(state).collect();
+    for symbol in 0..automaton.alphabet().len() {
+      for next in automaton.step(&single, symbol).intersection(allowed) {
+        match color.get(next) {
+          Some(1) => return true,
+          Some(_) => {}
+          None => {
+            if visit(automaton, allowed, *next, color) {
+              return true;
+            }
+          }
+        }
+      }
+    }
+    color.insert(state, 2);
+    false
+  }
+
+  for state in allowed {
+    if !color.contains_key(state) && visit(automaton, allowed, *state, &mut color) {
+      return true;
+    }
+  }
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Size;
+  use crate::afdn::Node;
+  use crate::util::file::ParsedFile;
+  use std::collections::HashMap;
+
+  fn automaton(transitions: &[(&str, &str, &str)], finals: &[&str]) -> ParsedFile {
+    let mut states: HashMap<String, Vec<Node>> = HashMap::new();
+    for (origin, character, destination) in transitions {
+      states.entry(origin.to_string()).or_default().push(Node {
+        character: character.to_string(),
+        destination: destination.to_string(),
+      });
+    }
+    ParsedFile::new(
+      vec!["q0".to_string()],
+      finals.iter().map(|e| e.to_string()).collect(),
+      states,
+      String::new(),
+    )
+  }
+
+  #[test]
+  fn enumerates_in_shortlex_order() {
+    // palavras que terminam em "b" sobre {a, b}
+    let infos = automaton(
+      &[("q0", "a", "q0"), ("q0", "b", "q0"), ("q0", "b", "q1")],
+      &["q1"],
+    );
+    let words: Vec<String> = super::enumerate(&infos, 2, 10)
+      .iter()
+      .map(|e| e.join(""))
+      .collect();
+    assert_eq!(words, vec!["b", "ab", "bb"]);
+    assert_eq!(super::enumerate(&infos, 10, 2).len(), 2);
+    assert_eq!(super::size(&infos), Size::Infinite);
+  }
+
+  #[test]
+  fn enumerates_symbols_missing_from_the_alphabet() {
+    // `b` é usado nas transições, mas não foi declarado
+    let mut infos = automaton(&[("q0", "a", "q0"), ("q0", "b", "q1")], &["q1"]);
+    infos.set_alphabet(Some(vec!["a".to_string()].into_iter().collect()));
+    let words: Vec<String> = super::enumerate(&infos, 2, 10)
+      .iter()
+      .map(|e| e.join(""))
+      .collect();
+    assert_eq!(words, vec!["b", "ab"]);
+
+    // lambda não pode ser lido como símbolo
+    let lambda = automaton(&[("q0", "/", "q1")], &["q1"]);
+    let active = vec!["q0".to_string()].into_iter().collect();
+    assert!(crate::afdn::afn::advance(&lambda, &active, "/").is_empty());
+  }
+
+  #[test]
+  fn classifies_finite_and_empty_languages() {
+    // {a, ab}, com uma lambda e um estado inútil com laço
+    let finite = automaton(
+      &[
+        ("q0", "a", "q1"),
+        ("q1", "b", "q2"),
+        ("q1", "/", "q2"),
+        ("q0", "c", "q3"),
+        ("q3", "c", "q3"),
+      ],
+      &["q2"],
+    );
+    assert_eq!(super::size(&finite), Size::Finite(2));
+
+    let empty = automaton(&[("q0", "a", "q0")], &["q1"]);
+    assert_eq!(super::size(&empty), Size::Empty);
+    assert!(super::enumerate(&empty, 5, 5).is_empty());
+  }
+
+  #[test]
+  fn counts_large_finite_languages() {
+    // todas as palavras de tamanho n sobre {a, b}: 2^n palavras
+    let chain = |n: usize| {
+      let transitions: Vec<(String, &str, String)> = (0..n)
+        .flat_map(|i| {
+          ["a", "b"]
+            .iter()
+            .map(move |c| (format!("q{}", i), *c, format!("q{}", i + 1)))
+        })
+        .collect();
+      let transitions: Vec<(&str, &str, &str)> = transitions
+        .iter()
+        .map(|(from, c, to)| (from.as_str(), *c, to.as_str()))
+        .collect();
+      automaton(&transitions, &[&format!("q{}", n)])
+    };
+    assert_eq!(super::size(&chain(40)), Size::Finite(1 << 40));
+    assert_eq!(super::size(&chain(70)), Size::Finite(usize::MAX));
+
+    let words = super::enumerate(&chain(40), 40, 3);
+    assert_eq!(words.len(), 3);
+    assert_eq!(words[2].join(""), format!("{}ba", "a".repeat(38)));
+
+    // palavras com mais de um caminho são contadas uma única vez
+    let ambiguous = automaton(&[("q0", "a", "q1"), ("q0", "a", "q2")], &["q1", "q2"]);
+    assert_eq!(super::size(&ambiguous), Size::Finite(1));
+  }
+
+  #[test]
+  fn finds_shortest_accepted_and_rejected_words() {
+    // palavras que terminam em "ab" sobre {a, b}
+    let infos = automaton(
+      &[
+        ("q0", "a", "q0"),
+        ("q0", "b", "q0"),
+        ("q0", "a", "q1"),
+        ("q1", "b", "q2"),
+      ],
+      &["q2"],
+    );
+    assert_eq!(super::shortest_accepted(&infos).unwrap(), vec!["a", "b"]);
+    assert!(super::shortest_rejected(&infos).unwrap().is_empty());
+
+    // número par de "a"s
+    let even = automaton(&[("q0", "a", "q1"), ("q1", "a", "q0")], &["q0"]);
+    assert!(super::shortest_accepted(&even).unwrap().is_empty());
+    assert_eq!(super::shortest_rejected(&even).unwrap(), vec!["a"]);
+
+    // a*, sobre {a, b}: "b" não possui transição (vai para a armadilha)
+    let partial = automaton(&[("q0", "a", "q0"), ("q1", "b", "q1")], &["q0"]);
+    assert_eq!(super::shortest_rejected(&partial).unwrap(), vec!["b"]);
+
+    // aceita todas as palavras
+    let all = automaton(&[("q0", "a", "q0")], &["q0"]);
+    assert_eq!(super::shortest_rejected(&all), None);
+
+    // `b` é usado nas transições, mas não foi declarado
+    let mut undeclared = automaton(&[("q0", "a", "q0"), ("q0", "b", "q1")], &["q0"]);
+    undeclared.set_alphabet(Some(vec!["a".to_string()].into_iter().collect()));
+    assert_eq!(super::shortest_rejected(&undeclared).unwrap(), vec!["b"]);
+  }
+}
@@ -0,0 +1,49 @@
+//! # afdn_animator
+//! Simulação, conversão e animação (via graphviz) de autômatos finitos determinísticos e não
+//! determinísticos.
+//!
+//! - [`Automaton`] e [`Simulator`]: modelo tipado (estados e símbolos internados), usado pelos
+//!   motores, pela determinização, pela minimização e pelas análises da linguagem, e simulação
+//!   passo a passo;
+//! - [`compiled`]: tabela densa (AFD) e adjacência esparsa (AFN) para palavras e autômatos grandes
+//!   (usada pela leitura em fluxo, em [`stream`]);
+//! - [`stream`]: simulação sobre uma palavra lida em blocos (arquivo ou entrada padrão);
+//! - [`parsers`]: leitura do arquivo de entrada;
+//! - [`compose`]: composição de autômatos de vários arquivos (`@module` e `@expr`);
+//! - [`exporters`]: escrita no formato de entrada e exportação para dot;
+//! - [`convert`] e [`language`]: conversões (lambda, completar, determinizar, minimizar, forma
+//!   canônica) e análises da linguagem;
+//! - [`afdn`]: os motores de simulação animada;
+//! - [`cli`]: os modos da linha de comando (o executável somente repassa os argumentos).
+#[macro_use]
+extern crate log;
+
+pub mod afdn;
+pub mod automaton;
+pub mod check;
+pub mod cli;
+pub mod compiled;
+pub mod compose;
+pub mod convert;
+pub mod dot;
+pub mod exercise;
+pub mod generate;
+pub mod language;
+pub mod stream;
+pub mod util;
+
+pub use automaton::{Automaton, Simulator};
+
+/// Leitura do arquivo de entrada.
+pub mod parsers {
+  pub use crate::util::file::{
+    parse, parse_str, tokenize, try_parse, try_parse_str, validate, ParseError, ParsedFile,
+    Validation,
+  };
+}
+
+/// Escrita do autômato no formato de entrada e exportação para o graphviz.
+pub mod exporters {
+  pub use crate::dot::{render, save_automaton};
+  pub use crate::util::file::{serialize, serialize_compact};
+}
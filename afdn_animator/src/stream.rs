@@ -0,0 +1,176 @@
+use crate::automaton::Automaton;
+use crate::compiled::{Compiled, Runner};
+use std::io::{self, Read};
+
+/// Tamanho dos blocos lidos da entrada.
+const CHUNK: usize = 64 * 1024;
+
+/// # Report
+/// Resultado da simulação sobre a palavra lida da entrada.
+#[derive(Debug, PartialEq)]
+pub struct Report {
+  pub accepted: bool,
+  /// Quantidade de símbolos consumidos (até o fim da entrada ou até travar).
+  pub consumed: usize,
+  /// Posição (quantidade de símbolos) em que a simulação travou, caso tenha travado.
+  pub stuck_at: Option<usize>,
+}
+
+/// Simula o autômato sobre a palavra lida de `reader` (e.g. um arquivo ou a entrada padrão),
+/// em blocos, sem manter a palavra inteira em memória.
+///
+/// Os espaços (e quebras de linha) apenas separam os símbolos e, em cada trecho, é feito o
+/// *longest match* sobre o alfabeto. Diferente do `tokenize`, um trecho entre espaços pode conter
+/// mais de um símbolo. A leitura é interrompida assim que a simulação trava.
+///
+/// ## Arguments
+///
+/// * `automaton` - O autômato (usado para identificar os símbolos).
+/// * `compiled` - O mesmo autômato, compilado.
+/// * `reader` - Origem da palavra.
+/// * `on_accepting` - Chamado com a posição (quantidade de símbolos consumidos) sempre que, após
+///   consumir um símbolo, algum estado final está ativo, i.e, o prefixo lido é aceito.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use afdn_animator::compiled::Compiled;
+/// # use afdn_animator::stream::run;
+/// # use afdn_animator::{parsers::parse_str, Automaton};
+/// # let automaton = Automaton::from_parsed(&parse_str("q0 ; q1\nq0 a > q1\nq1 a > q1\nwrd : a"));
+/// # let compiled = Compiled::new(&automaton);
+/// let report = run(&automaton, &compiled, std::io::stdin(), |position| println!("{}", position))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn run<R: Read, F: FnMut(usize)>(
+  automaton: &Automaton,
+  compiled: &Compiled,
+  mut reader: R,
+  mut on_accepting: F,
+) -> io::Result<Report> {
+  let max_len = automaton
+    .alphabet()
+    .iter()
+    .map(|e| e.len())
+    .max()
+    .unwrap_or(1);
+  let mut runner = Runner::new(compiled);
+  let mut consumed = 0;
+
+  // `pending` guarda o texto ainda não consumido e `partial` os bytes de um caracter UTF-8
+  // incompleto no fim do bloco
+  let mut pending = String::new();
+  let mut partial: Vec<u8> = Vec::new();
+  let mut buffer = vec![0u8; CHUNK];
+  let mut eof = false;
+  while !eof && !runner.is_stuck() {
+    let read = reader.read(&mut buffer)?;
+    eof = read == 0;
+    partial.extend_from_slice(&buffer[..read]);
+    let valid = match std::str::from_utf8(&partial) {
+      Ok(text) => text.len(),
+      Err(err) if err.error_len().is_none() && !eof => err.valid_up_to(),
+      Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+    };
+    pending.push_str(std::str::from_utf8(&partial[..valid]).unwrap());
+    partial.drain(..valid);
+
+    // consome enquanto o longest match estiver garantido (ou até o fim da entrada)
+    let mut start = 0;
+    loop {
+      let rest = pending[start..].trim_start();
+      start = pending.len() - rest.len();
+      if rest.is_empty() || (!eof && rest.len() < max_len && !rest.contains(char::is_whitespace)) {
+        break;
+      }
+
+      // só os primeiros `max_len` bytes podem formar o próximo símbolo
+      let window = rest
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take_while(|end| *end <= max_len)
+        .last()
+        .unwrap_or_else(|| rest.chars().next().unwrap().len_utf8());
+      let segment = rest[..window].split(char::is_whitespace).next().unwrap();
+      let size = (1..=max_len.min(segment.len()))
+        .rev()
+        .filter(|len| segment.is_char_boundary(*len))
+        .find(|len| automaton.symbol_id(&segment[..*len]).is_some())
+        .unwrap_or_else(|| segment.chars().next().unwrap().len_utf8());
+
+      runner.feed(automaton.symbol_id(&segment[..size]));
+      start += size;
+      consumed += 1;
+      if runner.is_stuck() {
+        break;
+      }
+      if runner.is_accepting() {
+        on_accepting(consumed);
+      }
+    }
+    pending.drain(..start);
+  }
+
+  let stuck_at = if runner.is_stuck() {
+    Some(consumed)
+  } else {
+    None
+  };
+  Ok(Report {
+    accepted: runner.is_accepting(),
+    consumed,
+    stuck_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Report;
+  use crate::automaton::Automaton;
+  use crate::compiled::Compiled;
+  use crate::util::file;
+  use std::io::Read;
+
+  /// Entrega no máximo 3 bytes por leitura, para testar as fronteiras entre blocos.
+  struct Trickle<'a>(&'a [u8]);
+  impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+      let size = self.0.len().min(buffer.len()).min(3);
+      buffer[..size].copy_from_slice(&self.0[..size]);
+      self.0 = &self.0[size..];
+      Ok(size)
+    }
+  }
+
+  #[test]
+  fn reports_accepted_prefixes_across_chunks() {
+    // terminadas em "10", com símbolos de mais de um caracter
+    let infos = file::parse_str(
+      "q0 ; q1\nalf : 1 10 ß\nq0 1 > q0\nq0 ß > q0\nq0 10 > q1\nq1 1 > q0\nq1 10 > q1\nwrd : ",
+    );
+    let automaton = Automaton::from_parsed(&infos);
+    let compiled = Compiled::new(&automaton);
+
+    let mut positions = Vec::new();
+    let report = super::run(
+      &automaton,
+      &compiled,
+      Trickle("1ß10 1\n1010".as_bytes()),
+      |e| positions.push(e),
+    )
+    .unwrap();
+    assert_eq!(
+      report,
+      Report {
+        accepted: true,
+        consumed: 6,
+        stuck_at: None
+      }
+    );
+    assert_eq!(positions, vec![3, 5, 6]);
+
+    let report = super::run(&automaton, &compiled, Trickle(b"10x10"), |_| {}).unwrap();
+    assert_eq!(report.stuck_at, Some(2));
+    assert!(!report.accepted);
+  }
+}
@@ -1,38 +1,280 @@
 pub mod file {
   use crate::afdn::{afn::LAMBDA, Node, NodeVec};
-  use std::{collections::HashMap, fs};
+  use crate::compose;
+  use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+  };
+
+  /// Coringa do campo de símbolo: "qualquer outro símbolo" do alfabeto que o estado ainda não usa.
+  pub const WILDCARD: &str = "*";
+
+  /// Setas aceitas entre o símbolo e o destino de uma transição.
+  const ARROWS: [&str; 2] = [">", "->"];
+
+  /// Torna literal o caractere seguinte, caso ele seja um dos [`ESCAPED`] (e.g. `\#x` é o nome
+  /// `#x`, e não um comentário).
+  const ESCAPE: char = '\\';
+
+  /// Caracteres com significado especial nos nomes de estados e símbolos: comentário, listas,
+  /// intervalos, coringa e seções (e.g. `0\-9` e `a\,b` são símbolos, e não três e dois
+  /// símbolos, e `alf\:` é um estado, e não a linha do alfabeto).
+  const ESCAPED: [char; 6] = [ESCAPE, '#', ',', '-', '*', ':'];
+
+  /// Atributos de exibição (`chave=valor`) de um estado ou transição, e.g. `label` e `color`.
+  pub type Attributes = BTreeMap<String, String>;
 
   /// # ParsedFile
   /// Struct usada para segurar todas as informações do arquivo de entrada (centraliza)
-  #[derive(Debug)]
+  #[derive(Debug, Clone)]
   pub struct ParsedFile {
-    initial_states: String,
+    initial_states: Vec<String>,
     finish_states: Vec<String>,
-    pub states: HashMap<String, NodeVec>,
+    states: HashMap<String, NodeVec>,
     word: String,
     is_afd: bool,
+    hidden_states: Vec<String>,
+    alphabet: Option<BTreeSet<String>>,
+    /// Atributos das linhas `@state`, por estado.
+    state_attributes: BTreeMap<String, Attributes>,
+    /// Atributos das linhas `@edge`, por transição `(origem, símbolo, destino)`.
+    edge_attributes: BTreeMap<(String, String, String), Attributes>,
   }
   impl ParsedFile {
-    pub fn get_initial_state(&self) -> &String {
+    /// Cria um novo arquivo "parsed" a partir de um autômato já montado (usado pelas conversões).
+    /// Será considerado um AFD caso exista um único estado inicial, não exista transição lambda e
+    /// nenhum estado repita o símbolo.
+    pub fn new(
+      initial_states: Vec<String>,
+      finish_states: Vec<String>,
+      states: HashMap<String, NodeVec>,
+      word: String,
+    ) -> ParsedFile {
+      let is_afd = initial_states.len() == 1
+        && states.values().all(|nodes| {
+          nodes.iter().enumerate().all(|(index, node)| {
+            node.character != LAMBDA
+              && !nodes[index + 1..]
+                .iter()
+                .any(|e| e.character == node.character)
+          })
+        });
+
+      ParsedFile {
+        initial_states,
+        finish_states,
+        states,
+        word,
+        is_afd,
+        hidden_states: Vec::new(),
+        alphabet: None,
+        state_attributes: BTreeMap::new(),
+        edge_attributes: BTreeMap::new(),
+      }
+    }
+    pub fn get_initial_states(&self) -> &Vec<String> {
       &self.initial_states
     }
     pub fn get_finish_state(&self) -> &Vec<String> {
       &self.finish_states
     }
-    pub fn get_word(&self) -> &String {
+    pub fn get_word(&self) -> &str {
       &self.word
     }
-    pub fn is_afd(&self) -> &bool {
-      &self.is_afd
+    pub fn set_word(&mut self, word: String) {
+      self.word = word;
+    }
+    pub fn is_afd(&self) -> bool {
+      self.is_afd
+    }
+    /// Transições de cada estado de origem. Somente leitura, já que o `is_afd` é calculado na
+    /// criação.
+    pub fn get_states(&self) -> &HashMap<String, NodeVec> {
+      &self.states
+    }
+    /// Estados que não devem ser desenhados nos arquivos dot (e.g. estado armadilha).
+    pub fn get_hidden_states(&self) -> &Vec<String> {
+      &self.hidden_states
+    }
+    pub fn set_hidden_states(&mut self, hidden_states: Vec<String>) {
+      self.hidden_states = hidden_states;
+    }
+    /// Todos os estados do autômato, inclusive os que não possuem transições de saída.
+    pub fn get_all_states(&self) -> BTreeSet<String> {
+      let mut all_states: BTreeSet<String> = BTreeSet::new();
+      all_states.extend(self.initial_states.iter().cloned());
+      all_states.extend(self.finish_states.iter().cloned());
+      for (origin, nodes) in &self.states {
+        all_states.insert(origin.to_string());
+        all_states.extend(nodes.iter().map(|e| e.destination.to_string()));
+      }
+      all_states
+    }
+    /// Palavra dividida em símbolos do alfabeto (veja [`tokenize`]).
+    pub fn get_tokens(&self) -> Vec<String> {
+      self::tokenize(&self.word, &self.get_alphabet())
+    }
+    /// Alfabeto declarado na linha `alf :` (caso exista).
+    pub fn get_declared_alphabet(&self) -> Option<&BTreeSet<String>> {
+      self.alphabet.as_ref()
+    }
+    pub fn set_alphabet(&mut self, alphabet: Option<BTreeSet<String>>) {
+      self.alphabet = alphabet;
+    }
+    /// Atributos de exibição dos estados (linhas `@state`).
+    pub fn get_state_attributes(&self) -> &BTreeMap<String, Attributes> {
+      &self.state_attributes
+    }
+    pub fn set_state_attribute(&mut self, state: &str, key: &str, value: &str) {
+      self
+        .state_attributes
+        .entry(state.to_string())
+        .or_default()
+        .insert(key.to_string(), value.to_string());
+    }
+    /// Copia os atributos de exibição de outro autômato (e.g. o original de uma conversão), somente
+    /// dos estados e transições que continuam existindo.
+    pub fn copy_attributes(&mut self, from: &ParsedFile) {
+      let all_states = self.get_all_states();
+      for (state, attributes) in from.get_state_attributes() {
+        if all_states.contains(state) {
+          self
+            .state_attributes
+            .insert(state.to_string(), attributes.clone());
+        }
+      }
+      for ((origin, symbol, destination), attributes) in from.get_edge_attributes() {
+        let exists = self.states.get(origin).is_some_and(|nodes| {
+          nodes
+            .iter()
+            .any(|e| &e.character == symbol && &e.destination == destination)
+        });
+        if exists {
+          self.edge_attributes.insert(
+            (
+              origin.to_string(),
+              symbol.to_string(),
+              destination.to_string(),
+            ),
+            attributes.clone(),
+          );
+        }
+      }
+    }
+    /// Atributos de exibição das transições (linhas `@edge`), por `(origem, símbolo, destino)`.
+    pub fn get_edge_attributes(&self) -> &BTreeMap<(String, String, String), Attributes> {
+      &self.edge_attributes
+    }
+    pub fn set_edge_attribute(
+      &mut self,
+      (origin, symbol, destination): (&str, &str, &str),
+      key: &str,
+      value: &str,
+    ) {
+      self
+        .edge_attributes
+        .entry((
+          origin.to_string(),
+          symbol.to_string(),
+          destination.to_string(),
+        ))
+        .or_default()
+        .insert(key.to_string(), value.to_string());
+    }
+    /// Alfabeto declarado (ou inferido) somado aos símbolos usados nas transições, mesmo que não
+    /// declarados. Usado pelas conversões, para que a linguagem seja sempre preservada.
+    pub fn get_full_alphabet(&self) -> BTreeSet<String> {
+      let mut alphabet = self.get_alphabet();
+      alphabet.extend(
+        self
+          .states
+          .values()
+          .flatten()
+          .filter(|e| e.character != LAMBDA)
+          .map(|e| e.character.to_string()),
+      );
+      alphabet
+    }
+    /// Alfabeto do autômato: o declarado na linha `alf :` ou, caso não exista, o inferido a partir
+    /// dos símbolos usados nas transições (lambda não faz parte).
+    pub fn get_alphabet(&self) -> BTreeSet<String> {
+      if let Some(alphabet) = &self.alphabet {
+        return alphabet.clone();
+      }
+      self
+        .states
+        .values()
+        .flatten()
+        .filter(|e| e.character != LAMBDA)
+        .map(|e| e.character.to_string())
+        .collect()
+    }
+  }
+
+  /// Divide a palavra em símbolos do alfabeto, permitindo símbolos com mais de um caracter
+  /// (e.g. `10` ou `if`).
+  ///
+  /// - Caso a palavra possua espaços, cada parte separada por espaço é um símbolo;
+  /// - Caso contrário, é feito o *longest match*, i.e, em cada posição é consumido o maior símbolo
+  ///   do alfabeto que seja prefixo do restante da palavra. Se nenhum símbolo casar, consome um
+  ///   único caracter (que será reportado como fora do alfabeto).
+  ///
+  /// ## Arguments
+  ///
+  /// * `word` - Palavra a ser dividida.
+  /// * `alphabet` - Alfabeto do autômato.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::parsers::tokenize;
+  /// # use std::collections::BTreeSet;
+  /// # let alphabet: BTreeSet<String> = vec!["1".to_string(), "10".to_string(), "a".to_string()].into_iter().collect();
+  /// let tokens = tokenize("10a", &alphabet); // ["10", "a"], caso "10" pertença ao alfabeto
+  /// # assert_eq!(tokens, vec!["10", "a"]);
+  /// ```
+  pub fn tokenize(word: &str, alphabet: &BTreeSet<String>) -> Vec<String> {
+    if word.contains(char::is_whitespace) {
+      return word.split_whitespace().map(|e| e.to_string()).collect();
+    }
+
+    let mut tokens: Vec<String> = Vec::new();
+    let mut rest: &str = word;
+    while let Some(first) = rest.chars().next() {
+      let size = alphabet
+        .iter()
+        .filter(|symbol| !symbol.is_empty() && rest.starts_with(symbol.as_str()))
+        .map(|symbol| symbol.len())
+        .max()
+        .unwrap_or_else(|| first.len_utf8());
+
+      tokens.push(rest[..size].to_string());
+      rest = &rest[size..];
+    }
+
+    debug!("Tokens: {:?}", &tokens);
+    tokens
+  }
+
+  /// Junta os símbolos de volta em uma palavra (para exibição). Caso algum símbolo tenha mais de
+  /// um caracter, são separados por espaço.
+  pub fn join_tokens(tokens: &[String]) -> String {
+    if tokens.iter().any(|e| e.chars().count() > 1) {
+      tokens.join(" ")
+    } else {
+      tokens.join("")
     }
   }
 
   /// Faz o parse de um arquivo de entrada.
   ///
   /// <pre>
-  /// Exemplo de um arquivo de entrada (note que pode haver transição lambda):
+  /// Exemplo de um arquivo de entrada (note que pode haver transição lambda e mais de um estado
+  /// inicial, separados por espaço, antes do `;`):
   ///
   /// s0 ; s2
+  /// alf : a b
   /// s0 a > s0
   /// s0 b > s1
   /// s1 a > s1
@@ -42,126 +284,1263 @@ pub mod file {
   /// wrd : aabb
   /// </pre>
   ///
+  /// A linha `alf :` (alfabeto) é opcional. Caso não exista, o alfabeto será inferido das transições.
+  ///
+  /// As linhas podem vir em qualquer ordem, as linhas vazias são ignoradas e o `#` inicia um
+  /// comentário até o fim da linha. A seta das transições é opcional e pode ser `>` ou `->`
+  /// (e.g. `s0 a s1` ou `s0 a -> s1`).
+  ///
   /// ## Arguments
   ///
   /// * `filepath` - Nome relativo do arquivo partindo do diretório raíz.
   ///
   /// ## Examples
   ///
-  /// ```rust
+  /// ```rust,no_run
+  /// # use afdn_animator::parsers::parse;
   /// let infos = parse("./input.txt");
   /// ```
-  pub fn parse(filepath: &String) -> ParsedFile {
+  pub fn parse(filepath: &str) -> ParsedFile {
     debug!("Parsing file...");
+    match self::try_parse(filepath) {
+      Ok(infos) => infos,
+      Err(err) => panic!("{}", err),
+    }
+  }
+
+  /// # ParseError
+  /// Erros encontrados ao ler um arquivo de entrada malformado.
+  #[derive(Debug, Clone, PartialEq)]
+  pub enum ParseError {
+    /// Nenhuma linha possui o `;` entre os estados iniciais e finais.
+    MissingSeparator,
+    MissingInitialState,
+    /// Transição sem origem, símbolo e destino (com o número da linha, a partir de 1).
+    InvalidTransition(usize),
+    /// Segunda linha de estados, `alf`, `wrd`, `@expr` ou `@module` com o mesmo nome (com o número
+    /// da linha, a partir de 1).
+    DuplicateSection(usize),
+    /// Estado com o nome de uma seta (`>` ou `->`).
+    ReservedName(String),
+    /// Linha `@state`/`@edge` malformada ou de um estado/transição inexistente (com o número da
+    /// linha, a partir de 1).
+    InvalidAttribute(usize),
+    /// Arquivo (de um `@include` ou `@module`) que não pôde ser lido.
+    MissingFile(String),
+    /// Arquivos que se incluem em ciclo, do primeiro até ele mesmo.
+    IncludeCycle(Vec<String>),
+    /// Erro na linha `@expr` ou `@module` (com o número da linha, a partir de 1).
+    Composition(usize, compose::Error),
+    /// Erro em um arquivo incluído ou módulo.
+    InFile(String, Box<ParseError>),
+  }
+  impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+      match self {
+        ParseError::MissingSeparator => write!(
+          f,
+          "O arquivo deve possuir uma linha separando os estados iniciais dos finais com ;"
+        ),
+        ParseError::MissingInitialState => {
+          write!(f, "O arquivo deve possuir ao menos um estado inicial")
+        }
+        ParseError::InvalidTransition(line) => write!(
+          f,
+          "Transição inválida na linha {}. Use <origem> <símbolo> > <destino>",
+          line
+        ),
+        ParseError::DuplicateSection(line) => write!(
+          f,
+          "A linha {} repete os estados iniciais/finais, o alf, o wrd, a @expr ou um @module",
+          line
+        ),
+        ParseError::ReservedName(name) => {
+          write!(f, "{} é uma seta e não pode ser o nome de um estado", name)
+        }
+        ParseError::MissingFile(file) => write!(f, "Não foi possível ler o arquivo {}", file),
+        ParseError::IncludeCycle(files) => {
+          write!(f, "Inclusão circular: {}", files.join(" -> "))
+        }
+        ParseError::Composition(line, error) => {
+          write!(f, "Erro na composição da linha {}: {}", line, error)
+        }
+        ParseError::InFile(file, error) => write!(f, "{}: {}", file, error),
+        ParseError::InvalidAttribute(line) => write!(
+          f,
+          "Atributo inválido na linha {}. Use @state <estado> chave=valor ou @edge <origem> <símbolo> <destino> chave=valor",
+          line
+        ),
+      }
+    }
+  }
+
+  /// Faz o parse do conteúdo de um arquivo de entrada já carregado (veja o [`parse`]).
+  /// Em caso de erro, encerra o programa com a mensagem (veja o [`try_parse_str`]).
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::parsers::parse_str;
+  /// let infos = parse_str("q0 ; q1\nq0 a > q1\nwrd : a");
+  /// ```
+  pub fn parse_str(parsed_file: &str) -> ParsedFile {
+    match self::try_parse_str(parsed_file) {
+      Ok(infos) => infos,
+      Err(err) => panic!("{}", err),
+    }
+  }
+
+  /// Remove o comentário da linha. O `#` inicia um comentário somente no início da linha ou após
+  /// um espaço, de modo que `a#b` ainda pode ser um símbolo. Nas linhas de atributos (`@`), o `#`
+  /// entre aspas também não é um comentário (e.g. `color="#ff0000"`).
+  fn strip_comment(line: &str) -> &str {
+    let attributes = line.trim_start().starts_with('@');
+    let mut quoted = false;
+    let mut previous = ' ';
+    for (index, character) in line.char_indices() {
+      if character == '"' && attributes {
+        quoted = !quoted;
+      } else if character == '#' && previous.is_whitespace() && !quoted {
+        return &line[..index];
+      }
+      previous = character;
+    }
+    line
+  }
+
+  /// Remove os escapes de um nome lido do arquivo (inverso do [`escape`]).
+  fn unescape(token: &str) -> String {
+    let mut output = String::with_capacity(token.len());
+    let mut chars = token.chars().peekable();
+    while let Some(character) = chars.next() {
+      match chars.peek() {
+        Some(next) if character == ESCAPE && ESCAPED.contains(next) => {
+          output.push(*next);
+          chars.next();
+        }
+        _ => output.push(character),
+      }
+    }
+    output
+  }
+
+  /// Escreve um nome de estado ou símbolo de modo que seja lido de volta sem alterações: o `#` no
+  /// início (que seria um comentário), o `:` que tornaria a linha uma seção (no início do nome ou
+  /// em `wrd:` e `alf:`) e o `\` antes de um caractere especial recebem o escape.
+  fn escape(token: &str) -> String {
+    let mut output = String::with_capacity(token.len());
+    let mut chars = token.chars().peekable();
+    let mut first = true;
+    while let Some(character) = chars.next() {
+      let escaped = match character {
+        '#' => first,
+        ':' => first || (chars.peek().is_none() && (output == "wrd" || output == "alf")),
+        ESCAPE => chars.peek().is_some_and(|e| ESCAPED.contains(e)),
+        _ => false,
+      };
+      if escaped {
+        output.push(ESCAPE);
+      }
+      output.push(character);
+      first = false;
+    }
+    output
+  }
+
+  /// Escreve um símbolo de modo que o campo de símbolo da transição não seja expandido: caso o
+  /// [`escape`] não baste, as vírgulas, o `-` de um intervalo e o coringa também recebem o escape.
+  fn escape_symbol(symbol: &str) -> String {
+    let plain = self::escape(symbol);
+    if plain != WILDCARD && self::expand_symbols(&plain) == [symbol] {
+      return plain;
+    }
+    self::escape_listed(symbol)
+  }
+
+  /// Escreve um símbolo como um item de uma lista (`a,b,c`): as vírgulas, o `-` de um intervalo e
+  /// o coringa sempre recebem o escape, mesmo que o símbolo sozinho não fosse expandido (e.g. `,`).
+  fn escape_listed(symbol: &str) -> String {
+    if symbol == WILDCARD {
+      return format!("{}{}", ESCAPE, WILDCARD);
+    }
+    let mut escaped = self::escape(symbol).replace(',', "\\,");
+    if self::symbol_range(symbol).is_some() {
+      escaped = escaped.replace('-', "\\-");
+    }
+    escaped
+  }
+
+  /// Divide o texto nas ocorrências de `separator` que não receberam o escape, mantendo os
+  /// escapes em cada parte.
+  fn split_unescaped(text: &str, separator: char) -> Vec<&str> {
+    let mut parts: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, character) in text.char_indices() {
+      if escaped {
+        escaped = false;
+      } else if character == ESCAPE {
+        escaped = true;
+      } else if character == separator {
+        parts.push(&text[start..index]);
+        start = index + character.len_utf8();
+      }
+    }
+    parts.push(&text[start..]);
+    parts
+  }
+
+  /// Primeira palavra do texto e o restante, e.g. o nome do estado em `@state q0 color=red`.
+  fn next_token(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+      Some(index) => Some((&text[..index], &text[index..])),
+      None if !text.is_empty() => Some((text, "")),
+      None => None,
+    }
+  }
+
+  /// Lê os atributos `chave=valor` de uma linha `@state`/`@edge`. O valor pode estar entre aspas
+  /// (e.g. `label="estado par"`), mas não pode conter aspas.
+  fn parse_attributes(text: &str) -> Option<Attributes> {
+    let mut attributes = Attributes::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+      let (key, value) = rest.split_once('=')?;
+      if key.is_empty() || key.contains(|e: char| e.is_whitespace() || e == '"') {
+        return None;
+      }
+      let (value, remaining) = match value.strip_prefix('"') {
+        Some(quoted) => {
+          let (value, remaining) = quoted.split_once('"')?;
+          if !remaining.is_empty() && !remaining.starts_with(char::is_whitespace) {
+            return None;
+          }
+          (value, remaining)
+        }
+        None => {
+          let end = value.find(char::is_whitespace).unwrap_or(value.len());
+          if value[..end].contains('"') {
+            return None;
+          }
+          (&value[..end], &value[end..])
+        }
+      };
+      attributes.insert(key.to_string(), value.to_string());
+      rest = remaining.trim_start();
+    }
+    Some(attributes)
+  }
+
+  /// Expande o campo de símbolo de uma transição: listas separadas por vírgula (`a,b,c`) e
+  /// intervalos de letras ou dígitos (`0-9`, `a-f`). Um campo que não forma uma lista válida (e.g.
+  /// `,` ou `a,#`) é um único símbolo. Com o escape, `,` e `-` são lidos literalmente (`a\,b`,
+  /// `0\-9`).
+  fn expand_symbols(field: &str) -> Vec<String> {
+    let items: Vec<&str> = self::split_unescaped(field, ',');
+    // um item iniciado por `#` seria lido como comentário ao escrever o símbolo sozinho
+    if items.iter().any(|e| e.is_empty() || e.starts_with('#')) {
+      return vec![self::unescape(field)];
+    }
+    let mut symbols: Vec<String> = Vec::new();
+    for item in items {
+      match self::symbol_range(item) {
+        Some((first, last)) => symbols.extend((first..=last).map(|e| e.to_string())),
+        None => symbols.push(self::unescape(item)),
+      }
+    }
+    symbols
+  }
+
+  /// Limites de um intervalo `<primeiro>-<último>`, caso ambos sejam da mesma classe (dígitos,
+  /// minúsculas ou maiúsculas ASCII) e estejam em ordem.
+  fn symbol_range(item: &str) -> Option<(char, char)> {
+    let class = |c: &char| {
+      (
+        c.is_ascii_digit(),
+        c.is_ascii_lowercase(),
+        c.is_ascii_uppercase(),
+      )
+    };
+    match item.chars().collect::<Vec<char>>().as_slice() {
+      [first, '-', last]
+        if first.is_ascii_alphanumeric() && class(first) == class(last) && first < last =>
+      {
+        Some((*first, *last))
+      }
+      _ => None,
+    }
+  }
+
+  /// Agrupa os símbolos na forma compacta aceita pelo parse (e.g. `0-9,x`): as sequências de três
+  /// ou mais letras ou dígitos consecutivos viram intervalos. Os demais símbolos recebem o escape
+  /// (e.g. o símbolo `,` vira `\,`), para que a lista não seja ambígua.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::util::file::compact_symbols;
+  /// assert_eq!(compact_symbols(&["a", "b", "c", "x"]), "a-c,x");
+  /// assert_eq!(compact_symbols(&[",", "a"]), "\\,,a");
+  /// ```
+  pub fn compact_symbols(symbols: &[&str]) -> String {
+    let mut symbols: Vec<&str> = symbols.to_vec();
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    let mut items: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < symbols.len() {
+      // avança enquanto os símbolos formam um intervalo válido
+      let mut end = index;
+      while end + 1 < symbols.len() {
+        let range = format!("{}-{}", symbols[index], symbols[end + 1]);
+        let consecutive = symbols[end].chars().count() == 1
+          && symbols[end + 1].chars().next().map(|e| e as u32)
+            == symbols[end].chars().next().map(|e| e as u32 + 1);
+        if !consecutive || self::symbol_range(&range).is_none() {
+          break;
+        }
+        end += 1;
+      }
+      if end - index >= 2 {
+        items.push(format!("{}-{}", symbols[index], symbols[end]));
+      } else {
+        items.extend(symbols[index..=end].iter().map(|e| self::escape_listed(e)));
+      }
+      index = end + 1;
+    }
+    items.join(",")
+  }
+
+  /// Valor de uma linha `<chave> : <valor>` (e.g. `alf : a b`), caso a chave seja `key`. A chave
+  /// deve ser uma palavra inteira, seguida do `:` (`wrd:x ; q1` é o cabeçalho de um estado
+  /// chamado `wrd:x`).
+  fn section<'l>(line: &'l str, key: &str) -> Option<&'l str> {
+    let (name, rest) = next_token(line)?;
+    if name == format!("{}:", key) {
+      return Some(rest);
+    }
+    if name != key {
+      return None;
+    }
+    rest.trim_start().strip_prefix(':')
+  }
+
+  /// Faz o parse do conteúdo de um arquivo de entrada, retornando o erro caso seja malformado.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::parsers::{try_parse_str, ParseError, ParsedFile};
+  /// let result: Result<ParsedFile, ParseError> = try_parse_str("q0 q1\nwrd : a");
+  /// # assert_eq!(result.unwrap_err(), ParseError::MissingSeparator);
+  /// ```
+  pub fn try_parse_str(parsed_file: &str) -> Result<ParsedFile, ParseError> {
+    self::parse_source(parsed_file, None, &mut Vec::new())
+  }
+
+  /// Faz o parse de um arquivo de entrada, retornando o erro caso seja malformado. Os arquivos das
+  /// linhas `@include` e `@module` são procurados a partir do diretório do arquivo.
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::parsers::{try_parse, ParseError, ParsedFile};
+  /// let result: Result<ParsedFile, ParseError> = try_parse("./inputs/default.txt");
+  /// ```
+  pub fn try_parse(filepath: &str) -> Result<ParsedFile, ParseError> {
+    let path = Path::new(filepath);
+    let content = self::read(path)?;
+    self::parse_source(&content, Some(path), &mut vec![self::identity(path)])
+  }
+
+  /// Linha do arquivo de entrada, já sem o comentário.
+  struct Line {
+    text: String,
+    /// Número da linha (a partir de 1) no arquivo de onde ela veio.
+    number: usize,
+    /// Arquivo incluído (`@include`) de onde veio a linha, ou `None` para o próprio arquivo.
+    file: Option<String>,
+  }
+  impl Line {
+    /// Associa o erro ao arquivo incluído de onde veio a linha (caso exista).
+    fn error(&self, error: ParseError) -> ParseError {
+      match &self.file {
+        Some(file) => ParseError::InFile(file.to_string(), Box::new(error)),
+        None => error,
+      }
+    }
+  }
+
+  fn read(path: &Path) -> Result<String, ParseError> {
+    fs::read_to_string(path)
+      .map_err(|err| ParseError::MissingFile(format!("{} ({})", path.display(), err)))
+  }
+
+  /// Caminho usado para detectar inclusões circulares (o mesmo arquivo por caminhos diferentes).
+  fn identity(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+  }
+
+  /// Resolve o caminho de um `@include`/`@module` a partir do arquivo que o referencia, verificando
+  /// se ele já está sendo lido (inclusão circular).
+  fn resolve(target: &str, from: Option<&Path>, stack: &[PathBuf]) -> Result<PathBuf, ParseError> {
+    let from = from.ok_or_else(|| {
+      ParseError::MissingFile(format!("{} (inclusão sem um arquivo de origem)", target))
+    })?;
+    let path = from.parent().unwrap_or_else(|| Path::new(".")).join(target);
+    let identity = self::identity(&path);
+    if let Some(start) = stack.iter().position(|e| *e == identity) {
+      let mut cycle: Vec<String> = stack[start..]
+        .iter()
+        .map(|e| e.display().to_string())
+        .collect();
+      cycle.push(identity.display().to_string());
+      return Err(ParseError::IncludeCycle(cycle));
+    }
+    Ok(path)
+  }
+
+  /// Lê as linhas do conteúdo, substituindo as linhas `@include <arquivo>` pelas linhas do arquivo.
+  fn read_lines(
+    content: &str,
+    path: Option<&Path>,
+    file: Option<&str>,
+    stack: &mut Vec<PathBuf>,
+    lines: &mut Vec<Line>,
+  ) -> Result<(), ParseError> {
+    for (line_index, line) in content.lines().enumerate() {
+      let line = Line {
+        text: strip_comment(line).trim().to_string(),
+        number: line_index + 1,
+        file: file.map(|e| e.to_string()),
+      };
+      let target = match next_token(&line.text) {
+        Some(("@include", target)) => target.trim(),
+        _ => {
+          if !line.text.is_empty() {
+            lines.push(line);
+          }
+          continue;
+        }
+      };
+      if target.is_empty() {
+        return Err(line.error(ParseError::MissingFile(format!(
+          "@include sem arquivo na linha {}",
+          line.number
+        ))));
+      }
+
+      let included = self::resolve(target, path, stack).map_err(|err| line.error(err))?;
+      let content = self::read(&included).map_err(|err| line.error(err))?;
+      debug!("Including {}", included.display());
+      stack.push(self::identity(&included));
+      let name = included.display().to_string();
+      self::read_lines(&content, Some(&included), Some(&name), stack, lines)?;
+      stack.pop();
+    }
+    Ok(())
+  }
+
+  /// Faz o parse do conteúdo. `path` é o arquivo de origem (para resolver os `@include` e
+  /// `@module`) e `stack` os arquivos sendo lidos (para detectar inclusões circulares).
+  fn parse_source(
+    parsed_file: &str,
+    path: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+  ) -> Result<ParsedFile, ParseError> {
+    let mut lines: Vec<Line> = Vec::new();
+    self::read_lines(parsed_file, path, None, stack, &mut lines)?;
+
+    // classifica as linhas (em qualquer ordem)
+    let mut header: Option<&Line> = None;
+    let mut alphabet: Option<BTreeSet<String>> = None;
+    let mut word: Option<String> = None;
+    let mut transitions: Vec<&Line> = Vec::new();
+    // linhas `@state` e `@edge`, aplicadas ao final (os estados podem ser declarados depois)
+    let mut directives: Vec<(&Line, &str, &str)> = Vec::new();
+    // autômatos nomeados (`@module <nome> <arquivo>`) e a expressão (`@expr`) que os combina
+    let mut modules: Vec<(&Line, &str, &str)> = Vec::new();
+    let mut expression: Option<(&Line, &str)> = None;
+    for line in &lines {
+      let text = line.text.as_str();
+      // `<origem> <símbolo> > <destino>` (como escrito pelo `serialize`) é sempre uma transição,
+      // mesmo que o estado se chame `wrd` ou o símbolo seja `;`
+      let tokens: Vec<&str> = text.split_whitespace().collect();
+      let duplicated = if tokens[0] == "@state" || tokens[0] == "@edge" {
+        directives.push((line, tokens[0], &text[tokens[0].len()..]));
+        false
+      } else if tokens[0] == "@module" {
+        match tokens[1..] {
+          [name, file] if !modules.iter().any(|e| e.1 == name) => {
+            modules.push((line, name, file));
+            false
+          }
+          [_, _] => true,
+          _ => {
+            return Err(line.error(ParseError::Composition(
+              line.number,
+              compose::Error::Syntax("use @module <nome> <arquivo>".to_string()),
+            )))
+          }
+        }
+      } else if tokens[0] == "@expr" {
+        expression
+          .replace((line, &text[tokens[0].len()..]))
+          .is_some()
+      } else if tokens.len() == 4 && ARROWS.contains(&tokens[2]) {
+        transitions.push(line);
+        false
+      } else if let Some(value) = section(text, "wrd") {
+        word.replace(self::unescape(value.trim())).is_some()
+      } else if let Some(value) = section(text, "alf") {
+        // alfabeto declarado (opcional)
+        let symbols = value.split_whitespace().map(self::unescape).collect();
+        alphabet.replace(symbols).is_some()
+      } else if text.contains(';') {
+        header.replace(line).is_some()
+      } else {
+        transitions.push(line);
+        false
+      };
+      if duplicated {
+        return Err(line.error(ParseError::DuplicateSection(line.number)));
+      }
+    }
+    debug!("Declared alphabet: {:#?}", &alphabet);
 
-    let parsed_file: String =
-      fs::read_to_string(filepath).expect("Houve um problema ao ler o arquivo");
-    debug!("File readed");
+    // sem a linha wrd, a palavra é vazia (o estado é o próprio inicial)
+    let word: String = word.unwrap_or_default();
 
-    // separa em linhas
-    let file_in_lines: Vec<&str> = parsed_file.split("\n").collect();
-    debug!("File splitted into lines: {:#?}", &file_in_lines);
+    let mut infos = match expression {
+      Some((line, expression)) => {
+        let composition = |error| line.error(ParseError::Composition(line.number, error));
+        if header.is_some() || !transitions.is_empty() {
+          return Err(composition(compose::Error::Syntax(
+            "o arquivo com @expr não pode possuir estados ou transições próprios".to_string(),
+          )));
+        }
+
+        let mut loaded: HashMap<String, ParsedFile> = HashMap::new();
+        for (line, name, file) in modules {
+          let module = self::resolve(file, path, stack).map_err(|err| line.error(err))?;
+          let content = self::read(&module).map_err(|err| line.error(err))?;
+          stack.push(self::identity(&module));
+          let parsed = self::parse_source(&content, Some(&module), stack).map_err(|err| {
+            line.error(ParseError::InFile(
+              module.display().to_string(),
+              Box::new(err),
+            ))
+          })?;
+          stack.pop();
+          loaded.insert(name.to_string(), parsed);
+        }
+
+        let mut composed = compose::compose(expression, &loaded, &word).map_err(composition)?;
+        if alphabet.is_some() {
+          composed.set_alphabet(alphabet);
+        }
+        composed
+      }
+      None => self::build(header, alphabet, word, transitions)?,
+    };
 
+    // atributos de exibição: somente de estados e transições existentes
+    let all_states = infos.get_all_states();
+    for (line, directive, rest) in directives {
+      let invalid = line.error(ParseError::InvalidAttribute(line.number));
+      if directive == "@state" {
+        let (state, rest) = next_token(rest).ok_or_else(|| invalid.clone())?;
+        let state = self::unescape(state);
+        let attributes = parse_attributes(rest).ok_or_else(|| invalid.clone())?;
+        if !all_states.contains(&state) {
+          return Err(invalid);
+        }
+        for (key, value) in attributes {
+          infos.set_state_attribute(&state, &key, &value);
+        }
+      } else {
+        let (origin, rest) = next_token(rest).ok_or_else(|| invalid.clone())?;
+        let (field, rest) = next_token(rest).ok_or_else(|| invalid.clone())?;
+        let (destination, rest) = next_token(rest).ok_or_else(|| invalid.clone())?;
+        let (origin, destination) = (self::unescape(origin), self::unescape(destination));
+        let attributes = parse_attributes(rest).ok_or_else(|| invalid.clone())?;
+        let exists = |symbol: &str| {
+          infos.states.get(&origin).is_some_and(|nodes| {
+            nodes
+              .iter()
+              .any(|e| e.character == symbol && e.destination == destination)
+          })
+        };
+        // o campo pode ser uma lista/intervalo, como nas transições
+        let symbols = if exists(&self::unescape(field)) {
+          vec![self::unescape(field)]
+        } else {
+          self::expand_symbols(field)
+        };
+        if !symbols.iter().all(|e| exists(e)) {
+          return Err(invalid);
+        }
+        for symbol in symbols {
+          for (key, value) in &attributes {
+            infos.set_edge_attribute((&origin, &symbol, &destination), key, value);
+          }
+        }
+      }
+    }
+    debug!("State attributes: {:#?}", infos.get_state_attributes());
+
+    Ok(infos)
+  }
+
+  /// Monta o autômato a partir das linhas de estados iniciais/finais e de transições.
+  fn build(
+    header: Option<&Line>,
+    alphabet: Option<BTreeSet<String>>,
+    word: String,
+    transitions: Vec<&Line>,
+  ) -> Result<ParsedFile, ParseError> {
     // estados iniciais (até N estados, separados por N*espaço) ; estados finais (até n estados, j*espaço)
-    let initial_end_states: Vec<&str> = file_in_lines[0].split(";").collect();
-    let initial_states: Vec<&str> = initial_end_states[0]
+    let header = header.ok_or(ParseError::MissingSeparator)?;
+    let initial_end_states: Vec<&str> = header.text.split(';').collect();
+    let initial_states: Vec<String> = initial_end_states[0]
       .split_whitespace()
-      .collect::<Vec<&str>>();
+      .map(self::unescape)
+      .collect();
+    if initial_states.is_empty() {
+      return Err(header.error(ParseError::MissingInitialState));
+    }
     let end_states: Vec<String> = initial_end_states[1]
       .split_whitespace()
-      .collect::<Vec<&str>>()
-      .iter()
-      .map(|e| e.to_string())
+      .map(self::unescape)
       .collect();
+    if let Some(state) = initial_states
+      .iter()
+      .chain(&end_states)
+      .find(|e| ARROWS.contains(&e.as_str()))
+    {
+      return Err(header.error(ParseError::ReservedName(state.to_string())));
+    }
     debug!(
       "Splitted initial {:#?} and final states {:#?}",
       &initial_states, &end_states
     );
-
-    // última linha será a palavra de teste
-    let max = file_in_lines.len() - 1;
-    debug!("The file has {} states", max - 1);
+    debug!("The file has {} transitions", transitions.len());
 
     // obtém os estados e suas transições
-    debug!("Creating the hashmap");
-    let mut is_afdn = false;
-    let mut afdn: HashMap<String, NodeVec> = HashMap::new();
+    // (origem, símbolo, destino), já com as listas e intervalos expandidos
+    let mut edges: Vec<(String, String, String)> = Vec::new();
+    let mut wildcards: Vec<(String, String)> = Vec::new();
     debug!("Iterating over states:");
-    for line_index in 1..max {
-      let line: Vec<&str> = file_in_lines[line_index].split_whitespace().collect();
-      debug!("\t#{} Line -> {:#?}", line_index, &line);
-
-      let current_node = line[0].to_string();
+    for transition in transitions {
+      let line: Vec<&str> = transition.text.split_whitespace().collect();
+      debug!("\t#{} Line -> {:#?}", transition.number, &line);
+      // <origem> <símbolo(s)> [> ou ->] <destino>
+      let (current_node, field, destination) = match line.as_slice() {
+        [origin, field, destination] => (*origin, *field, *destination),
+        [origin, field, arrow, destination] if ARROWS.contains(arrow) => {
+          (*origin, *field, *destination)
+        }
+        _ => return Err(transition.error(ParseError::InvalidTransition(transition.number))),
+      };
       debug!("\t - Current node: {}", &current_node);
 
-      let character: String = line[1].to_string();
-      let destination: String = line.last().unwrap().to_string();
-      debug!("\t - {} -> {}", &character, &destination);
+      let (current_node, destination) = (self::unescape(current_node), self::unescape(destination));
+      if field == WILDCARD {
+        // expandido ao final, quando o alfabeto e os demais símbolos do estado forem conhecidos
+        wildcards.push((current_node, destination));
+        continue;
+      }
+      for character in self::expand_symbols(field) {
+        edges.push((current_node.to_string(), character, destination.to_string()));
+      }
+    }
 
+    // o coringa recebe os símbolos do alfabeto (declarado ou inferido) que o estado não usa
+    if !wildcards.is_empty() {
+      let full_alphabet: BTreeSet<&String> = match &alphabet {
+        Some(alphabet) => alphabet.iter().collect(),
+        None => edges
+          .iter()
+          .map(|e| &e.1)
+          .filter(|e| *e != LAMBDA)
+          .collect(),
+      };
+      let mut expanded: Vec<(String, String, String)> = Vec::new();
+      for (origin, destination) in wildcards {
+        let used: HashSet<&String> = edges
+          .iter()
+          .filter(|e| e.0 == origin)
+          .map(|e| &e.1)
+          .collect();
+        for character in full_alphabet.iter().filter(|e| !used.contains(*e)) {
+          expanded.push((
+            origin.to_string(),
+            character.to_string(),
+            destination.to_string(),
+          ));
+        }
+      }
+      edges.extend(expanded);
+    }
+
+    debug!("Creating the hashmap");
+    let mut is_afdn = false;
+    let mut afdn: HashMap<String, NodeVec> = HashMap::new();
+    let mut symbols: HashMap<String, HashSet<String>> = HashMap::new();
+    for (current_node, character, destination) in edges {
+      debug!("\t - {}: {} -> {}", &current_node, &character, &destination);
       let new_node = Node {
         character: String::from(&character),
         destination,
       };
 
-      debug!("\t - Check if exist this key ({}) in Hash", &current_node);
-      if afdn.contains_key(&current_node) {
-        debug!("\t - Already exists. Adding new node");
-        let mut cvec: NodeVec = afdn.get(&current_node).unwrap().to_vec();
-
-        // checa se é um AFN (símbolo Lambda ou repetidos)
-        if character != LAMBDA.to_string() {
-          cvec.insert(0, new_node);
-
-          // precisa checar se já é AFDN, para evitar sobrescrever a flag
-          if !is_afdn {
-            debug!("\t - Checking if is AFDN...");
-            // se não for afdn ainda, faz um map e confere se tem chave duplicada.
-            let repeated: Vec<String> = cvec.iter().map(|e| e.character.to_string()).collect();
-            debug!("\t - Repeated_vector {:#?}", &repeated);
-            is_afdn = repeated
-              .iter()
-              .enumerate()
-              // analisa do ponto atual para frente, i.e, se no futuro irá existir um elemento igual/repetido
-              .any(|(index, word)| repeated[index + 1..].contains(&word));
-
-            debug!("\t - Is it an AFDN? R.: {:?}", is_afdn);
-          }
-        } else {
-          // se for lambda, insere no fim e muda para um AFD
-          is_afdn = true;
-          cvec.push(new_node);
-        }
-
-        debug!("\t - Updated nodes: {:?}", cvec);
-        afdn.insert(current_node, cvec);
-      } else {
-        debug!("\t - Key didn't exists. Adding into HashMap");
-        afdn.insert(current_node, vec![new_node]);
+      // checa se é um AFN (símbolo lambda ou repetido no mesmo estado)
+      let seen = symbols.entry(current_node.clone()).or_default();
+      if character == LAMBDA || !seen.insert(character) {
+        is_afdn = true;
       }
+      debug!("\t - Is it an AFDN? R.: {:?}", is_afdn);
+
+      afdn.entry(current_node).or_default().push(new_node);
     }
 
-    // obtém a última linha e divide com base no char ":"
-    let word: Vec<&str> = file_in_lines.last().unwrap().trim().split(":").collect();
-    let word: String = match word.len() {
-      // obtém a palavra a ser testada
-      2 => word.last().unwrap().trim().to_string(),
-      // se não houver nada, significa que o estado é o próprio inicial, i.e, palavra vazia
-      _ => String::from(""),
-    };
+    // mantém a ordem das transições: as com símbolo da última para a primeira lida e, em
+    // seguida, as lambda (na ordem em que foram lidas)
+    for nodes in afdn.values_mut() {
+      let (mut ordered, lambdas): (NodeVec, NodeVec) =
+        nodes.drain(..).partition(|e| e.character != LAMBDA);
+      ordered.reverse();
+      ordered.extend(lambdas);
+      *nodes = ordered;
+    }
 
     debug!("Initial states: {:#?}", &initial_states);
     debug!("Finish states:  {:#?}", &end_states);
     debug!("Word:           {:#?}", &word);
     debug!("HashMap:        {:#?}", &afdn);
 
-    // return three things
-    ParsedFile {
-      // estou assumindo que só é possível ter um único estado inicial
-      initial_states: initial_states[0].to_string(),
+    // mais de um estado inicial também caracteriza um AFN
+    let is_afd = !is_afdn && initial_states.len() == 1;
+
+    Ok(ParsedFile {
+      initial_states,
       finish_states: end_states,
       word,
       states: afdn,
-      is_afd: !is_afdn,
+      is_afd,
+      hidden_states: Vec::new(),
+      alphabet,
+      state_attributes: BTreeMap::new(),
+      edge_attributes: BTreeMap::new(),
+    })
+  }
+
+  /// Escreve o autômato no mesmo formato do arquivo de entrada (inverso do [`parse`]), com uma
+  /// transição por linha. Os estados e transições são ordenados para que a saída seja sempre a mesma.
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - Arquivo parsed, contendo as informações
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::util::file::serialize;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// println!("{}", serialize(&infos));
+  /// ```
+  pub fn serialize(infos: &ParsedFile) -> String {
+    self::write(infos, false)
+  }
+
+  /// Igual ao [`serialize`], mas agrupa em uma única linha (veja o [`compact_symbols`]) as
+  /// transições entre os mesmos estados, e.g. `s0 0-9,x > s1`.
+  pub fn serialize_compact(infos: &ParsedFile) -> String {
+    self::write(infos, true)
+  }
+
+  fn write(infos: &ParsedFile, compact: bool) -> String {
+    let names = |names: &[String]| -> String {
+      names
+        .iter()
+        .map(|e| self::escape(e))
+        .collect::<Vec<String>>()
+        .join(" ")
+    };
+    let mut output = format!(
+      "{} ; {}\n",
+      names(infos.get_initial_states()),
+      names(infos.get_finish_state())
+    );
+
+    if let Some(alphabet) = infos.get_declared_alphabet() {
+      let symbols: Vec<String> = alphabet.iter().cloned().collect();
+      output.push_str(&format!("alf : {}\n", names(&symbols)));
+    }
+
+    let mut keys: Vec<&String> = infos.states.keys().collect();
+    keys.sort();
+    for origin in keys {
+      let mut nodes: NodeVec = infos.states.get(origin).unwrap().to_vec();
+      nodes.sort_by(|a, b| (&a.character, &a.destination).cmp(&(&b.character, &b.destination)));
+
+      // (símbolo ou lista de símbolos, destino)
+      let mut lines: Vec<(String, &str)> = Vec::new();
+      if compact {
+        let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for node in &nodes {
+          // símbolos que seriam lidos como lista, intervalo ou coringa ficam em linhas próprias
+          let symbol = self::escape_symbol(&node.character);
+          if node.character.contains(',') || symbol != node.character {
+            lines.push((symbol, &node.destination));
+          } else {
+            groups
+              .entry(&node.destination)
+              .or_default()
+              .push(&node.character);
+          }
+        }
+        for (destination, symbols) in groups {
+          lines.push((self::compact_symbols(&symbols), destination));
+        }
+      } else {
+        lines.extend(
+          nodes
+            .iter()
+            .map(|e| (self::escape_symbol(&e.character), e.destination.as_str())),
+        );
+      }
+
+      for (symbols, destination) in lines {
+        output.push_str(&format!(
+          "{} {} > {}\n",
+          self::escape(origin),
+          symbols,
+          self::escape(destination)
+        ));
+      }
+    }
+
+    let attributes = |attributes: &Attributes| -> String {
+      attributes
+        .iter()
+        .map(|(key, value)| format!(" {}=\"{}\"", key, value))
+        .collect()
+    };
+    for (state, values) in infos.get_state_attributes() {
+      output.push_str(&format!(
+        "@state {}{}\n",
+        self::escape(state),
+        attributes(values)
+      ));
+    }
+    for ((origin, symbol, destination), values) in infos.get_edge_attributes() {
+      output.push_str(&format!(
+        "@edge {} {} {}{}\n",
+        self::escape(origin),
+        self::escape_symbol(symbol),
+        self::escape(destination),
+        attributes(values)
+      ));
+    }
+
+    // cada símbolo da palavra pode iniciar um comentário
+    let word: Vec<String> = infos
+      .get_word()
+      .split_whitespace()
+      .map(|e| e.to_string())
+      .collect();
+    output.push_str(&format!("wrd : {}", names(&word)));
+    output
+  }
+
+  /// # Validation
+  /// Resultado da validação do autômato (e da palavra) contra o alfabeto.
+  #[derive(Debug, Default)]
+  pub struct Validation {
+    /// Símbolos usados nas transições, mas que não foram declarados no alfabeto.
+    pub undeclared: Vec<String>,
+    /// Símbolos da palavra que não pertencem ao alfabeto.
+    pub word_undeclared: Vec<String>,
+    /// Pares `(estado, símbolo)` sem transição (somente para AFDs).
+    pub missing: Vec<(String, String)>,
+    /// Símbolos declarados no alfabeto, mas que não são usados em nenhuma transição.
+    pub unused: Vec<String>,
+  }
+  impl Validation {
+    pub fn is_word_valid(&self) -> bool {
+      self.word_undeclared.is_empty()
+    }
+    /// Mensagens (uma por problema encontrado) para exibir ao usuário.
+    pub fn messages(&self) -> Vec<String> {
+      let mut messages: Vec<String> = Vec::new();
+      for symbol in &self.undeclared {
+        messages.push(format!(
+          "Símbolo {} usado nas transições, mas não declarado no alfabeto",
+          symbol
+        ));
+      }
+      for symbol in &self.word_undeclared {
+        messages.push(format!(
+          "Símbolo {} da palavra não pertence ao alfabeto",
+          symbol
+        ));
+      }
+      for (state, symbol) in &self.missing {
+        messages.push(format!("Transição ausente: δ({}, {})", state, symbol));
+      }
+      for symbol in &self.unused {
+        messages.push(format!(
+          "Símbolo {} declarado no alfabeto, mas nunca usado",
+          symbol
+        ));
+      }
+      messages
+    }
+  }
+
+  /// Valida as transições e a palavra contra o alfabeto (declarado ou inferido).
+  ///
+  /// ## Arguments
+  ///
+  /// * `infos` - Arquivo parsed, contendo as informações
+  ///
+  /// ## Examples
+  ///
+  /// ```rust
+  /// # use afdn_animator::parsers::validate;
+  /// # use afdn_animator::parsers::parse_str;
+  /// # let infos = parse_str("q0 ; q1\nq0 a > q0\nq0 a > q1\nwrd : a");
+  /// let validation = validate(&infos);
+  /// for message in validation.messages() { eprintln!("{}", message); }
+  /// ```
+  pub fn validate(infos: &ParsedFile) -> Validation {
+    let alphabet = infos.get_alphabet();
+    let used: BTreeSet<String> = infos
+      .states
+      .values()
+      .flatten()
+      .filter(|e| e.character != LAMBDA)
+      .map(|e| e.character.to_string())
+      .collect();
+
+    let mut validation = Validation::default();
+    if infos.get_declared_alphabet().is_some() {
+      validation.undeclared = used.difference(&alphabet).cloned().collect();
+      validation.unused = alphabet.difference(&used).cloned().collect();
+    }
+
+    for symbol in infos.get_tokens() {
+      if !alphabet.contains(&symbol) && !validation.word_undeclared.contains(&symbol) {
+        validation.word_undeclared.push(symbol);
+      }
+    }
+
+    if infos.is_afd() {
+      for state in infos.get_all_states() {
+        for symbol in &alphabet {
+          let has_transition = match infos.states.get(&state) {
+            Some(nodes) => nodes.iter().any(|e| &e.character == symbol),
+            None => false,
+          };
+          if !has_transition {
+            validation
+              .missing
+              .push((state.to_string(), symbol.to_string()));
+          }
+        }
+      }
+    }
+
+    debug!("Validation: {:#?}", &validation);
+    validation
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use crate::afdn::Node;
+    use std::collections::HashMap;
+
+    #[test]
+    fn validates_against_declared_alphabet() {
+      let mut states = HashMap::new();
+      states.insert(
+        "s0".to_string(),
+        vec![Node {
+          character: "d".to_string(),
+          destination: "s0".to_string(),
+        }],
+      );
+      let mut infos = super::ParsedFile::new(
+        vec!["s0".to_string()],
+        vec!["s0".to_string()],
+        states,
+        "ax".to_string(),
+      );
+      infos.set_alphabet(Some(vec!["a".to_string()].into_iter().collect()));
+
+      let validation = super::validate(&infos);
+      assert_eq!(validation.undeclared, vec!["d"]);
+      assert_eq!(validation.unused, vec!["a"]);
+      assert_eq!(validation.word_undeclared, vec!["x"]);
+      assert_eq!(
+        validation.missing,
+        vec![("s0".to_string(), "a".to_string())]
+      );
+      assert!(!validation.is_word_valid());
+    }
+
+    #[test]
+    fn tokenizes_multi_character_symbols() {
+      let alphabet = vec!["1".to_string(), "10".to_string(), "if".to_string()]
+        .into_iter()
+        .collect();
+      assert_eq!(super::tokenize("101if", &alphabet), vec!["10", "1", "if"]);
+      assert_eq!(super::tokenize("1 10 if", &alphabet), vec!["1", "10", "if"]);
+      assert_eq!(super::tokenize("1x", &alphabet), vec!["1", "x"]);
+      assert!(super::tokenize("", &alphabet).is_empty());
+    }
+
+    #[test]
+    fn reports_malformed_files() {
+      use super::ParseError;
+      let parse = |content: &str| super::try_parse_str(content).err();
+      assert_eq!(parse("q0 q1\nwrd : a"), Some(ParseError::MissingSeparator));
+      assert_eq!(
+        parse(" ; q1\nwrd : a"),
+        Some(ParseError::MissingInitialState)
+      );
+      assert_eq!(
+        parse("q0 ; q1\nq0 a > q1\nq1\nwrd : a"),
+        Some(ParseError::InvalidTransition(3))
+      );
+      assert_eq!(parse(""), Some(ParseError::MissingSeparator));
+      assert_eq!(parse("q0 ; q1\nalf : a"), None);
+      assert_eq!(
+        parse("q0 ; q1\nwrd : a\nq0 a > q1\nwrd : b"),
+        Some(ParseError::DuplicateSection(4))
+      );
+      assert_eq!(
+        parse("q0 ; q1\nq0 a => q1"),
+        Some(ParseError::InvalidTransition(2))
+      );
+      assert_eq!(
+        parse("q0;> q1\nq0 a > q1"),
+        Some(ParseError::ReservedName(">".to_string()))
+      );
+      // com a seta, a linha é uma transição (e não a palavra)
+      assert_eq!(parse("q0 ; q1\nwrd : > q1\nwrd : a"), None);
+    }
+
+    #[test]
+    fn expands_symbol_lists_ranges_and_wildcard() {
+      let infos = super::parse_str(
+        "s0 ; s2\nalf : 0 1 2 3 x y ,\ns0 0-3,x > s1\ns0 * > s2\ns1 , > s1\ns1 y,/ > s2\nwrd : ",
+      );
+      assert!(!infos.is_afd());
+      assert_eq!(
+        super::serialize(&infos),
+        "s0 ; s2\nalf : , 0 1 2 3 x y\n\
+         s0 , > s2\ns0 0 > s1\ns0 1 > s1\ns0 2 > s1\ns0 3 > s1\ns0 x > s1\ns0 y > s2\n\
+         s1 , > s1\ns1 / > s2\ns1 y > s2\nwrd : "
+      );
+
+      let compact = super::serialize_compact(&infos);
+      assert_eq!(
+        compact,
+        "s0 ; s2\nalf : , 0 1 2 3 x y\ns0 , > s2\ns0 0-3,x > s1\ns0 y > s2\n\
+         s1 , > s1\ns1 /,y > s2\nwrd : "
+      );
+      assert_eq!(
+        super::serialize(&super::parse_str(&compact)),
+        super::serialize(&infos)
+      );
+
+      // sem alfabeto declarado, o coringa usa os símbolos das demais transições
+      let infos = super::parse_str("q0 ; q1\nq0 a > q0\nq0 * > q1\nq1 b-a > q1\nwrd : ");
+      assert!(infos.is_afd());
+      assert_eq!(
+        super::serialize(&infos),
+        "q0 ; q1\nq0 a > q0\nq0 b-a > q1\nq1 b-a > q1\nwrd : "
+      );
+    }
+
+    #[test]
+    fn reads_state_and_edge_attributes() {
+      use super::ParseError;
+      let content = "@state par label=\"estado par\" color=\"#2a6f97\" pos=1,2 # comentário\n\
+                     par ; par\n\
+                     par a,b > impar\n\
+                     impar a,b > par\n\
+                     @edge par a,b impar style=dashed\n\
+                     wrd : ab";
+      let infos = super::parse_str(content);
+      let par = infos.get_state_attributes().get("par").unwrap();
+      assert_eq!(par.get("label").unwrap(), "estado par");
+      assert_eq!(par.get("color").unwrap(), "#2a6f97");
+      assert_eq!(par.get("pos").unwrap(), "1,2");
+      assert_eq!(infos.get_edge_attributes().len(), 2);
+
+      let serialized = super::serialize_compact(&infos);
+      assert_eq!(
+        serialized,
+        "par ; par\nimpar a,b > par\npar a,b > impar\n\
+         @state par color=\"#2a6f97\" label=\"estado par\" pos=\"1,2\"\n\
+         @edge par a impar style=\"dashed\"\n@edge par b impar style=\"dashed\"\nwrd : ab"
+      );
+      assert_eq!(
+        super::serialize(&super::parse_str(&serialized)),
+        super::serialize(&infos)
+      );
+
+      let parse = |content: &str| super::try_parse_str(content).err();
+      let invalid = Some(ParseError::InvalidAttribute(2));
+      assert_eq!(parse("q0 ; q0\n@state q1 color=red"), invalid);
+      assert_eq!(parse("q0 ; q0\n@state q0 color"), invalid);
+      assert_eq!(parse("q0 ; q0\n@state q0 label=\"aberto"), invalid);
+      assert_eq!(parse("q0 ; q0\n@edge q0 a q0 color=red"), invalid);
+    }
+
+    #[test]
+    fn includes_files_and_composes_modules() {
+      use super::ParseError;
+      let infos = super::try_parse("./tests/fixtures/modulos/identificador.txt").unwrap();
+      assert_eq!(infos.states.get("i1").unwrap().len(), 36);
+      assert!(infos.get_state_attributes().contains_key("i1"));
+
+      // sem o arquivo de origem não há como resolver o caminho
+      assert!(matches!(
+        super::try_parse_str("q0 ; q0\n@include outro.txt"),
+        Err(ParseError::MissingFile(_))
+      ));
+
+      match super::try_parse("./tests/fixtures/modulos/ciclo_a.txt") {
+        Err(ParseError::InFile(_, error)) => match *error {
+          ParseError::IncludeCycle(files) => {
+            assert_eq!(files.len(), 3);
+            assert!(files[0].ends_with("ciclo_a.txt") && files[2].ends_with("ciclo_a.txt"));
+          }
+          error => panic!("{}", error),
+        },
+        other => panic!("{:?}", other.err()),
+      }
+
+      let infos = super::try_parse("./tests/fixtures/composicao.txt").unwrap();
+      assert!(!infos.is_afd());
+      assert!(infos.get_all_states().contains("NUM2_n1"));
+      let accepts =
+        |word: &str| crate::check::accepts(&infos, &super::tokenize(word, &infos.get_alphabet()));
+      assert!(accepts("x1+42+y"));
+      assert!(!accepts("x1++42"));
+
+      assert!(matches!(
+        super::try_parse_str("@module A a.txt\n@expr A .\nq0 ; q0"),
+        Err(ParseError::Composition(2, _))
+      ));
+    }
+
+    #[test]
+    fn accepts_comments_and_any_section_order() {
+      let content = "# autômato de exemplo\n\
+                     \n\
+                     wrd : ab # palavra\n\
+                     q0 a -> q1   # com seta longa\n\
+                     \n\
+                     q1 b q1\n\
+                     q0 ; q1\n\
+                     alf : a b\n\
+                     a#b a > q0\n";
+      let infos = super::parse_str(content);
+      assert_eq!(
+        super::serialize(&infos),
+        "q0 ; q1\nalf : a b\na#b a > q0\nq0 a > q1\nq1 b > q1\nwrd : ab"
+      );
+      assert_eq!(
+        super::serialize(&super::parse_str("q0 ; q0\r\nq0 a > q0\r\n\r\n")),
+        "q0 ; q0\nq0 a > q0\nwrd : "
+      );
+    }
+
+    #[test]
+    fn escapes_names_that_look_like_comments() {
+      // estados `#` e `#x`, símbolo `#` e um estado com `\` antes do `#`
+      let infos =
+        super::parse_str("\\# ; \\#x\n\\# \\# > \\#x\n\\#x a > a\\\\#\nwrd : \\# a # comentário");
+      assert_eq!(infos.get_initial_states(), &vec!["#".to_string()]);
+      assert_eq!(infos.get_finish_state(), &vec!["#x".to_string()]);
+      assert!(infos.get_all_states().contains("a\\#"));
+      assert_eq!(infos.get_word(), "# a");
+
+      let written = super::serialize(&infos);
+      assert_eq!(
+        written,
+        "\\# ; \\#x\n\\# \\# > \\#x\n\\#x a > a\\\\#\nwrd : \\# a"
+      );
+      let again = super::parse_str(&written);
+      assert_eq!(super::serialize(&again), written);
+      assert!(crate::afdn::afd::accepts(&again, &["#".to_string()]));
+    }
+
+    #[test]
+    fn escapes_literal_symbols() {
+      // `0-9`, `a,b` e `*` como símbolos, ao lado de um intervalo e de um coringa de verdade
+      let infos = super::parse_str(
+        "q0 ; q1\nq0 0\\-9 > q1\nq0 a\\,b > q1\nq0 \\* > q1\nq0 c-d > q0\nq1 * > q0\nwrd : ",
+      );
+      let symbols = |origin: &str| -> Vec<String> {
+        let mut symbols: Vec<String> = infos.get_states()[origin]
+          .iter()
+          .map(|e| e.character.clone())
+          .collect();
+        symbols.sort();
+        symbols
+      };
+      assert_eq!(symbols("q0"), vec!["*", "0-9", "a,b", "c", "d"]);
+      assert_eq!(infos.get_full_alphabet().len(), 5);
+
+      for written in [super::serialize(&infos), super::serialize_compact(&infos)] {
+        assert!(written.contains("0\\-9") && written.contains("a\\,b"));
+        let again = super::parse_str(&written);
+        assert_eq!(super::serialize(&again), super::serialize(&infos));
+      }
+    }
+
+    #[test]
+    fn reads_sections_only_from_the_exact_keyword() {
+      // estados com `:` no nome não são confundidos com a linha da palavra
+      let infos = super::parse_str("wrd:x ; alf:y\nwrd:x a > alf:y\nwrd: a");
+      assert_eq!(infos.get_initial_states(), &vec!["wrd:x".to_string()]);
+      assert_eq!(infos.get_finish_state(), &vec!["alf:y".to_string()]);
+      assert_eq!(infos.get_word(), "a");
+      assert_eq!(super::parse_str("q0 ; q0\nwrd :ab").get_word(), "ab");
+
+      // e, ao escrever, os nomes que formariam uma seção recebem o escape
+      let infos = super::parse_str("alf:;wrd\nwrd : > alf:\nwrd :x > wrd\nwrd : ");
+      let written = super::serialize(&infos);
+      assert_eq!(
+        written,
+        "alf\\: ; wrd\nwrd \\: > alf\\:\nwrd \\:x > wrd\nwrd : "
+      );
+      assert_eq!(super::serialize(&super::parse_str(&written)), written);
+    }
+
+    #[test]
+    fn reads_multiple_initial_states() {
+      let infos = super::parse_str("p0 q0 ; p1 q1\np0 a > p1\nq0 a > q1\nwrd : a");
+      assert_eq!(
+        infos.get_initial_states(),
+        &vec!["p0".to_string(), "q0".to_string()]
+      );
+      assert_eq!(
+        infos.get_finish_state(),
+        &vec!["p1".to_string(), "q1".to_string()]
+      );
+      // mesmo sem transições não determinísticas, não é um AFD
+      assert!(!infos.is_afd());
+      assert!(super::serialize(&infos).starts_with("p0 q0 ; p1 q1\n"));
     }
   }
 }
@@ -174,8 +1553,9 @@ use std::io;
 ///
 /// ## Examples
 ///
-/// ```rust
-/// let option:u8 = menu(); // returns 0 or 1 (save)
+/// ```rust,no_run
+/// # use afdn_animator::util::menu;
+/// let option:u8 = menu("ab".to_string()); // returns 0 or 1 (save)
 /// ```
 pub fn menu(word: String) -> u8 {
   debug!("Show menu");
@@ -203,3 +1583,52 @@ pub fn menu(word: String) -> u8 {
   debug!("Returning values");
   input
 }
+
+/// # Animation
+/// Configuração de onde salvar os quadros (arquivos dot) e se deve perguntar, a cada passo, se o
+/// quadro deve ser salvo (menu) ou salvar todos automaticamente.
+#[derive(Debug, Clone)]
+pub struct Animation {
+  pub dir: String,
+  pub interactive: bool,
+}
+impl Default for Animation {
+  fn default() -> Animation {
+    Animation {
+      dir: "./dot".to_string(),
+      interactive: true,
+    }
+  }
+}
+impl Animation {
+  /// Salva todos os quadros em `dir`, sem exibir o menu.
+  pub fn automatic(dir: &str) -> Animation {
+    Animation {
+      dir: dir.to_string(),
+      interactive: false,
+    }
+  }
+
+  /// Exibe o menu (caso interativo) até obter uma opção válida. Retorna se o quadro deve ser salvo.
+  ///
+  /// ## Arguments
+  /// - `word` - Palavra até agora
+  pub fn should_save(&self, word: String) -> bool {
+    if !self.interactive {
+      return true;
+    }
+    loop {
+      debug!("\t - Showing menu");
+      match menu(word.clone()) {
+        1 => return true,
+        0 => return false,
+        _ => continue,
+      }
+    }
+  }
+
+  /// Caminho do quadro `dotfile_<name>.dot` dentro de `dir`.
+  pub fn frame<T: std::fmt::Display>(&self, name: T) -> String {
+    format!("{}/dotfile_{}.dot", self.dir, name)
+  }
+}
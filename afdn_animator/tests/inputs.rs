@@ -0,0 +1,121 @@
+//! Testes de regressão: executa cada arquivo de `inputs/` e de `tests/fixtures/` (sem interação)
+//! no motor adequado e compara o resultado, o percurso e os quadros dot gerados com os arquivos
+//! de `tests/golden/<arquivo>/`.
+//!
+//! Para atualizar os arquivos esperados (após uma mudança intencional), execute
+//! `ATUALIZAR_GOLDEN=1 cargo test --test inputs`.
+use afdn_animator::afdn::{afd, afn};
+use afdn_animator::util::{file, Animation};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Arquivos de entrada (`.txt`) dos diretórios, em ordem.
+fn inputs() -> Vec<PathBuf> {
+  let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+  let mut files: Vec<PathBuf> = ["inputs", "tests/fixtures"]
+    .iter()
+    .flat_map(|dir| fs::read_dir(root.join(dir)).unwrap())
+    .map(|e| e.unwrap().path())
+    .filter(|e| e.extension().is_some_and(|ext| ext == "txt"))
+    .collect();
+  files.sort();
+  files
+}
+
+/// Resultado e percurso (conjuntos de estados ativos a cada símbolo) no formato do arquivo
+/// `esperado.txt`.
+fn describe(infos: &file::ParsedFile, accepted: bool) -> String {
+  let word = infos.get_tokens();
+  let steps = afn::simulate(infos, &word);
+
+  let sets: Vec<String> = steps
+    .iter()
+    .map(|e| {
+      format!(
+        "{{{}}}",
+        e.active.iter().cloned().collect::<Vec<_>>().join(", ")
+      )
+    })
+    .collect();
+  let mut path = sets[0].to_string();
+  for (symbol, set) in word.iter().zip(sets.iter().skip(1)) {
+    path.push_str(&format!(" --{}--> {}", symbol, set));
+  }
+
+  format!(
+    "motor: {}\naceita: {}\npercurso: {}\n",
+    if infos.is_afd() { "afd" } else { "afn" },
+    if accepted { "sim" } else { "não" },
+    path
+  )
+}
+
+/// Arquivos do diretório (nome -> conteúdo).
+fn read_all(dir: &Path) -> BTreeMap<String, String> {
+  fs::read_dir(dir)
+    .unwrap()
+    .map(|e| e.unwrap().path())
+    .map(|e| {
+      let name = e.file_name().unwrap().to_string_lossy().to_string();
+      (name, fs::read_to_string(&e).unwrap())
+    })
+    .collect()
+}
+
+#[test]
+fn inputs_match_golden_files() {
+  let update = std::env::var("ATUALIZAR_GOLDEN").is_ok();
+  let golden_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+  let mut failures: Vec<String> = Vec::new();
+
+  for input in inputs() {
+    let name = input.file_stem().unwrap().to_string_lossy().to_string();
+    let infos = file::parse(&input.to_string_lossy());
+
+    // quadros em um diretório temporário, sem o menu
+    let frames = std::env::temp_dir().join(format!("afdn_golden_{}", name));
+    let _ = fs::remove_dir_all(&frames);
+    fs::create_dir_all(&frames).unwrap();
+    let animation = Animation::automatic(&frames.to_string_lossy());
+    let accepted = if infos.is_afd() {
+      afd::run(&infos, &animation)
+    } else {
+      afn::run(&infos, &animation)
+    };
+
+    let mut actual = read_all(&frames);
+    actual.insert("esperado.txt".to_string(), describe(&infos, accepted));
+
+    let golden = golden_root.join(&name);
+    if update {
+      let _ = fs::remove_dir_all(&golden);
+      fs::create_dir_all(&golden).unwrap();
+      for (file, content) in &actual {
+        fs::write(golden.join(file), content).unwrap();
+      }
+      continue;
+    }
+
+    let expected = read_all(&golden);
+    if expected.keys().ne(actual.keys()) {
+      failures.push(format!(
+        "{}: quadros diferentes.\n  esperados: {:?}\n  gerados:   {:?}",
+        name,
+        expected.keys().collect::<Vec<_>>(),
+        actual.keys().collect::<Vec<_>>()
+      ));
+      continue;
+    }
+    for (file, content) in &actual {
+      if &expected[file] != content {
+        failures.push(format!(
+          "{}/{} difere do esperado:\n--- esperado\n{}\n--- gerado\n{}",
+          name, file, expected[file], content
+        ));
+      }
+    }
+  }
+
+  assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}